@@ -0,0 +1,7 @@
+use crate::{Article, Error, Gateway};
+
+impl Gateway {
+    pub async fn add_article(&self, article: &Article) -> Result<Article, Error> {
+        self.create(article).await
+    }
+}
@@ -1,27 +1,7 @@
-use crate::{Customer, Error, Gateway, BASE_URL};
-use serde::{Deserialize, Serialize};
+use crate::{Customer, Error, Gateway};
 
 impl Gateway {
-    pub async fn add_customer<'a>(&self, customer: &'a Customer) -> Result<Customer, Error> {
-        #[derive(Serialize)]
-        #[serde(rename_all = "PascalCase")]
-        struct Request<'a> {
-            customer: &'a Customer,
-        }
-
-        let body = Request {
-            customer: &customer,
-        };
-
-        let url = format!("{}/customers", BASE_URL);
-
-        #[derive(Deserialize)]
-        #[serde(rename_all = "PascalCase")]
-        struct Response {
-            customer: Customer,
-        }
-
-        let res: Response = self.post(&url, &body).await?;
-        Ok(res.customer)
+    pub async fn add_customer(&self, customer: &Customer) -> Result<Customer, Error> {
+        self.create(customer).await
     }
 }
@@ -0,0 +1,7 @@
+use crate::{Error, Gateway, Offer};
+
+impl Gateway {
+    pub async fn add_offer(&self, offer: &Offer) -> Result<Offer, Error> {
+        self.create(offer).await
+    }
+}
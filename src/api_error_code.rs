@@ -1,138 +1,291 @@
-use serde_repr::{Deserialize_repr, Serialize_repr};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+// What kind of problem an error code is about.
+#[derive(Serialize, Deserialize, Debug, PartialEq, Eq, Clone, Copy, Hash)]
+pub enum ApiErrorCategory {
+    // Fortnox failed; the request may well succeed if sent again.
+    System,
+    // The request itself was malformed, e.g. its headers or body.
+    Request,
+    // Credentials, licenses and scopes.
+    Auth,
+    // A value in the request was not accepted.
+    Validation,
+    // Something the request refers to does not exist.
+    NotFound,
+    // The request clashes with what is already there.
+    Conflict,
+    // The date falls in a financial period that has been locked for changes.
+    // None of the listed codes is in it yet, since the list only holds codes
+    // with a published description.
+    LockedPeriod,
+    // Accounts, VAT and balancing of vouchers and supplier invoices.
+    Accounting,
+    // The document is in a state that does not allow the change.
+    DocumentState,
+    // Errors from the warehouse module.
+    Warehouse,
+    // A code that is not listed in `ApiErrorCode`.
+    Other,
+}
+
+// Defines `ApiErrorCode` with a variant per listed code, plus `Other` for the
+// codes that are not listed, so that any code Fortnox sends can be represented.
+macro_rules! api_error_codes {
+    ($($name:ident = $code:literal => $category:ident, $description:literal,)*) => {
+        #[derive(Debug, PartialEq, Eq, Clone, Copy, Hash)]
+        pub enum ApiErrorCode {
+            $($name,)*
+            Other(u32),
+        }
+
+        impl ApiErrorCode {
+            pub fn from_code(code: u32) -> ApiErrorCode {
+                match code {
+                    $($code => ApiErrorCode::$name,)*
+                    code => ApiErrorCode::Other(code),
+                }
+            }
+
+            pub fn code(self) -> u32 {
+                match self {
+                    $(ApiErrorCode::$name => $code,)*
+                    ApiErrorCode::Other(code) => code,
+                }
+            }
+
+            // What the code means in English, as Fortnox documents it. The
+            // message that comes with an error is often in Swedish.
+            pub fn description(self) -> Option<&'static str> {
+                match self {
+                    $(ApiErrorCode::$name => Some($description),)*
+                    ApiErrorCode::Other(_) => None,
+                }
+            }
+
+            pub fn category(self) -> ApiErrorCategory {
+                match self {
+                    $(ApiErrorCode::$name => ApiErrorCategory::$category,)*
+                    ApiErrorCode::Other(_) => ApiErrorCategory::Other,
+                }
+            }
+        }
+    };
+}
 
-#[derive(Serialize_repr, Deserialize_repr, Debug, PartialEq, Clone, Copy)]
-#[repr(u32)]
-pub enum ApiErrorCode {
-    Unknown = 0,
+api_error_codes! {
+    // System
 
     // System exception
-    // Something went wrong on our end, please contact us.
-    SystemException = 1000003,
+    SystemException = 1000003 => System,
+        "Something went wrong on our end, please contact us.",
+
+    // Request
 
     // Invalid response type
-    // The provided response type(Accept) was invalid.
-    InvalidResponseType = 1000030,
+    InvalidResponseType = 1000030 => Request,
+        "The provided response type (Accept) was invalid.",
 
     // Invalid content type
-    // The provided content type was invalid.
-    InvalidContentType = 1000031,
+    InvalidContentType = 1000031 => Request,
+        "The provided content type was invalid.",
 
-    // Värdet måste vara alfanumeriskt ({Value})
-    // The value needs to be alphanumeric.
-    NotAlphanumeric = 2000106,
+    // Ingen eller felaktig typ av data.
+    IncorrectData = 2001392 => Request,
+        "The request body was empty or contained incorrect data.",
 
-    // Värdet måste vara numeriskt ({Value})
-    // The value needs to be numeric.
-    NotNumeric = 2000108,
+    // Inläsning av dokument misslyckades: {Message}
+    CouldNotReadDocument = 2001740 => Request,
+        "The XML object contained an error.",
 
-    // Värdet måste vara en boolean ({Value})
-    // The value needs to be boolean.
-    NotBoolean = 2000134,
+    // Error deserializing JSON: JSON_ERROR_SYNTAX
+    CouldNotDeserializeJson = 2002115 => Request,
+        "The JSON object contained an error.",
+
+    // Auth
 
     // Ogiltig inloggning
-    // The Client-Secret or the Access-Token is either missing or is incorrect.
-    InvalidCredentials = 2000310,
+    InvalidCredentials = 2000310 => Auth,
+        "The Client-Secret or the Access-Token is either missing or is incorrect.",
 
     // Kan inte logga in, access-token eller client-secret saknas(2).
-    // The Client-Secret or the Access-Token is either missing or is incorrect.
-    InvalidCredentials2 = 2000311,
+    InvalidCredentials2 = 2000311 => Auth,
+        "The Client-Secret or the Access-Token is either missing or is incorrect.",
+
+    // Api-licens saknas.
+    NoLicense = 2001103 => Auth,
+        "The requested Fortnox account does not have a license to use the API.",
+
+    // Det finns ingen aktiv licens för önskat scope
+    NoActiveLicenseForScope = 2001101 => Auth,
+        "There is no active license for the desired scope.",
+
+    // Har inte behörighet för scope
+    NoAccessToScope = 2000663 => Auth,
+        "No access to the current scope.",
+
+    // Ej autentiserad
+    NotAuthenticated = 2003275 => Auth,
+        "Not authenticated.",
+
+    // Validation
+
+    // Värdet måste vara alfanumeriskt ({Value})
+    NotAlphanumeric = 2000106 => Validation,
+        "The value needs to be alphanumeric.",
+
+    // Värdet måste vara numeriskt ({Value})
+    NotNumeric = 2000108 => Validation,
+        "The value needs to be numeric.",
+
+    // Värdet måste vara en boolean ({Value})
+    NotBoolean = 2000134 => Validation,
+        "The value needs to be boolean.",
 
     // Värdet innehåller ej tillåtna tecken. ({Value})
-    // The value contains invalid characters.
-    InvalidCharacters = 2000359,
+    InvalidCharacters = 2000359 => Validation,
+        "The value contains invalid characters.",
 
     // Ogiltig parameter i anropet.
-    // A parameter is invalid. Read more about parameters.
-    InvalidParameters = 2000588,
-
-    // Kundnummer 1 används redan. Kundnumret har redan använts men blivit raderat.
-    // Customer number 1 is/has already been used.
-    CustomerNumberHasAlreadyBeenUsed = 2000637,
+    InvalidParameters = 2000588 => Validation,
+        "A parameter is invalid.",
 
     // A valid identifier was not provided.
-    // A valid identifier was not provided.
-    AValidIdentifierWasNotProvided = 2000729,
-
-    // Api-licens saknas.
-    // The requested Fortnox account does not have a license to use the API.
-    NoLicense = 2001103,
+    AValidIdentifierWasNotProvided = 2000729 => Validation,
+        "A valid identifier was not provided.",
 
-    // Ingen eller felaktig typ av data.
-    // The request body was empty or contained incorrect data.
-    IncorrectData = 2001392,
+    // Felaktigt fältnamn
+    InvalidFieldName = 2001399 => Validation,
+        "Invalid field name.",
 
-    // Inläsning av dokument misslyckades: {Message}
-    // The XML object contained an error.
-    CouldNotReadDocument = 2001740,
+    // Utleveransdatum kan inte vara senare än dagens datum
+    DeliveryDateCannotBeLaterThanToday = 2003126 => Validation,
+        "The delivery date cannot be later than today.",
 
-    // Error deserializing JSON: JSON_ERROR_SYNTAX
-    // The JSON object contained an error.
-    CouldNotDeserializeJson = 2002115,
+    // NotFound
 
     // Kunde inte hitta konto
-    // Could not find Account.
-    AccountNotFound = 2001304,
+    AccountNotFound = 2001304 => NotFound,
+        "Could not find account.",
 
-    // Felaktigt fältnamn
-    // Invalid Field name.
-    InvalidFieldName = 2001399,
+    // Kunde inte hämta/hitta kund (kundnummer)
+    CouldNotFindCustomer = 2000204 => NotFound,
+        "The customer in the request is not available in the customer resource.",
 
-    // Det finns ingen aktiv licens för önskat scope
-    // There is no active licens for the desired scope.
-    NoActiveLicenseForScope = 2001101,
+    // Kunde inte hämta/hitta kund (kundnummer)
+    CouldNotFindCustomer2 = 2000433 => NotFound,
+        "The customer in the request is not available in the customer resource.",
 
-    // Har inte behörighet för scope
-    // No access to the current scope.
-    NoAccessToScope = 2000663,
+    // Kunde inte hitta artikel
+    CouldNotFindArticle = 2001302 => NotFound,
+        "Could not find article used in request.",
+
+    // Kan inte hitta artikeln
+    CouldNotFindArticle2 = 2000428 => NotFound,
+        "Could not find article used in request.",
+
+    // Conflict
+
+    // Kundnummer 1 används redan. Kundnumret har redan använts men blivit raderat.
+    CustomerNumberHasAlreadyBeenUsed = 2000637 => Conflict,
+        "The customer number is or has already been used.",
+
+    // Migrering är redan påbörjad eller avslutad
+    MigrationAlreadyStartedOrCompleted = 2003241 => Conflict,
+        "The migration has already been started or completed.",
+
+    // Accounting
 
     // Det saknas ett förvalt konto för Inköp SE, omvänd skattskyldighet
-    // Account is missing for Purchase SE reversed tax liability.
-    AccountIsMissingForPurchaseSeReversedTaxLiability = 2003095,
+    AccountIsMissingForPurchaseSeReversedTaxLiability = 2003095 => Accounting,
+        "Account is missing for Purchase SE reversed tax liability.",
 
     // Leverantörsfakturan balanserar inte
-    // Supplier invoice does not balance.
-    SupplierInvoiceDoesNotBalance = 2000755,
+    SupplierInvoiceDoesNotBalance = 2000755 => Accounting,
+        "Supplier invoice does not balance.",
 
     // Momsrader för momstyp REVERSE måste vara märkta med motsvarande CODE
-    // Tax Rows for VAT type REVERSE must be marked with CODE.
-    TaxRowsForVatTypeReverseMustBeMarkedWithCode = 2003115,
+    TaxRowsForVatTypeReverseMustBeMarkedWithCode = 2003115 => Accounting,
+        "Tax rows for VAT type REVERSE must be marked with CODE.",
+
+    // DocumentState
 
     // Enbart ordrar som levererats ut kan klarmarkeras
-    OnlyDeliveredOrdersCanBeMarkedCompleted = 2003124,
+    OnlyDeliveredOrdersCanBeMarkedCompleted = 2003124 => DocumentState,
+        "Only orders that have been delivered can be marked as completed.",
 
     // Ett klarmarkerat dokument kan inte ändras
-    CannotChangeCompletedDocument = 2003125,
+    CannotChangeCompletedDocument = 2003125 => DocumentState,
+        "A document that has been marked as completed cannot be changed.",
 
-    // Utleveransdatum kan inte vara senare än dagens datum
-    DeliveryDateCannotBeLaterThanToday = 2003126,
-
-    // Migrering är redan påbörjad eller avslutad
-    MigrationAlreadyStartedOrCompleted = 2003241,
-
-    // Ej autentiserad
-    NotAuthenticated = 2003275,
+    // Warehouse
 
     // Hittades inte i lagermodulen
-    CouldNotBeFoundInWarehouseModule = 2003277,
+    CouldNotBeFoundInWarehouseModule = 2003277 => Warehouse,
+        "Could not be found in the warehouse module.",
 
     // Dokumentet är makulerat i lagermodulen
-    DocumentIsDeletedInWarehouseModule = 2003399,
+    DocumentIsDeletedInWarehouseModule = 2003399 => Warehouse,
+        "The document is voided in the warehouse module.",
 
     // Ett fel uppstod i lagermodulen
-    AnErrorOccurredInWarehouseModule = 2003127,
+    AnErrorOccurredInWarehouseModule = 2003127 => Warehouse,
+        "An error occurred in the warehouse module.",
+}
 
-    // Kunde inte hämta/hitta kund (kundnummer)
-    // The customer in the request is not available in the customer resource.
-    CouldNotFindCustomer = 2000204,
+impl ApiErrorCode {
+    pub fn is_not_found(self) -> bool {
+        self.category() == ApiErrorCategory::NotFound
+            || self == ApiErrorCode::CouldNotBeFoundInWarehouseModule
+    }
+}
 
-    // Kunde inte hämta/hitta kund (kundnummer)
-    // The customer in the request is not available in the customer resource.
-    CouldNotFindCustomer2 = 2000433,
+impl From<u32> for ApiErrorCode {
+    fn from(code: u32) -> ApiErrorCode {
+        ApiErrorCode::from_code(code)
+    }
+}
 
-    // Kunde inte hitta artikel
-    // Could not find article used in request.
-    CouldNotFindArticle = 2001302,
+impl From<ApiErrorCode> for u32 {
+    fn from(code: ApiErrorCode) -> u32 {
+        code.code()
+    }
+}
 
-    // Kan inte hitta artikeln
-    CouldNotFindArticle2 = 2000428,
+impl Serialize for ApiErrorCode {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_u32(self.code())
+    }
+}
+
+// Never fails on a code that is not listed, see `ApiErrorCode::Other`.
+impl<'de> Deserialize<'de> for ApiErrorCode {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<ApiErrorCode, D::Error> {
+        u32::deserialize(deserializer).map(ApiErrorCode::from_code)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn keeps_unlisted_codes() {
+        let code: ApiErrorCode = serde_json::from_str("2999999").unwrap();
+        assert_eq!(code, ApiErrorCode::Other(2999999));
+        assert_eq!(code.category(), ApiErrorCategory::Other);
+        assert_eq!(code.description(), None);
+        assert_eq!(serde_json::to_string(&code).unwrap(), "2999999");
+    }
+
+    #[test]
+    fn looks_up_listed_codes() {
+        let code = ApiErrorCode::from_code(2001399);
+        assert_eq!(code, ApiErrorCode::InvalidFieldName);
+        assert_eq!(code.code(), 2001399);
+        assert_eq!(code.category(), ApiErrorCategory::Validation);
+        assert_eq!(code.description(), Some("Invalid field name."));
+        assert!(ApiErrorCode::from_code(2000433).is_not_found());
+        assert!(ApiErrorCode::CouldNotBeFoundInWarehouseModule.is_not_found());
+    }
 }
@@ -1,8 +1,289 @@
+use crate::order::serialize_unknown;
+use crate::validation::Validator;
+use crate::{lenient, Amount, ArticleType, FieldError, Quantity, Resource, ValidationMode};
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
 #[serde(rename_all = "PascalCase")]
 pub struct Article {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(default)]
+    #[serde(deserialize_with = "lenient::option_string")]
+    pub article_number: Option<String>,
+
+    pub description: String,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(default)]
+    #[serde(deserialize_with = "lenient::option")]
+    pub r#type: Option<ArticleType>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(default)]
+    #[serde(deserialize_with = "lenient::option_boolean")]
+    pub active: Option<bool>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(default)]
+    #[serde(deserialize_with = "lenient::option_string")]
+    pub unit: Option<String>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(rename = "EAN")]
+    #[serde(default)]
+    #[serde(deserialize_with = "lenient::option_string")]
+    pub ean: Option<String>,
+
+    // In percent, e.g. 25.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(rename = "VAT")]
+    #[serde(default)]
+    #[serde(deserialize_with = "lenient::option")]
+    pub vat: Option<u32>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(default)]
+    #[serde(deserialize_with = "lenient::option")]
+    pub purchase_price: Option<Amount>,
+
+    // From the default price list. Set prices through the price list.
+    #[serde(skip_serializing)]
+    #[serde(default)]
+    #[serde(deserialize_with = "lenient::option")]
+    pub sales_price: Option<Amount>, // Read-only.
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(default)]
+    #[serde(deserialize_with = "lenient::option")]
+    pub sales_account: Option<u32>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(rename = "EUAccount")]
+    #[serde(default)]
+    #[serde(deserialize_with = "lenient::option")]
+    pub eu_account: Option<u32>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(rename = "EUVATAccount")]
+    #[serde(default)]
+    #[serde(deserialize_with = "lenient::option")]
+    pub eu_vat_account: Option<u32>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(default)]
+    #[serde(deserialize_with = "lenient::option")]
+    pub export_account: Option<u32>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(default)]
+    #[serde(deserialize_with = "lenient::option")]
+    pub purchase_account: Option<u32>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(default)]
+    #[serde(deserialize_with = "lenient::option_boolean")]
+    pub stock_goods: Option<bool>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(default)]
+    #[serde(deserialize_with = "lenient::option")]
+    pub quantity_in_stock: Option<Quantity>,
+
+    #[serde(skip_serializing)]
+    #[serde(default)]
+    #[serde(deserialize_with = "lenient::option")]
+    pub reserved_quantity: Option<Quantity>, // Read-only.
+
+    #[serde(skip_serializing)]
+    #[serde(default)]
+    #[serde(deserialize_with = "lenient::option")]
+    pub disposable_quantity: Option<Quantity>, // Read-only.
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(default)]
+    #[serde(deserialize_with = "lenient::option_string")]
+    pub stock_place: Option<String>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(default)]
+    #[serde(deserialize_with = "lenient::option")]
+    pub stock_warning: Option<Quantity>,
+
+    #[serde(skip_serializing)]
+    #[serde(default)]
+    #[serde(deserialize_with = "lenient::option")]
+    pub stock_value: Option<Amount>, // Read-only.
+
+    // In grams.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(default)]
+    #[serde(deserialize_with = "lenient::option")]
+    pub weight: Option<u32>,
+
+    // In millimetres, as are height and depth.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(default)]
+    #[serde(deserialize_with = "lenient::option")]
+    pub width: Option<u32>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(default)]
+    #[serde(deserialize_with = "lenient::option")]
+    pub height: Option<u32>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(default)]
+    #[serde(deserialize_with = "lenient::option")]
+    pub depth: Option<u32>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(default)]
+    #[serde(deserialize_with = "lenient::option_boolean")]
+    pub bulky: Option<bool>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(default)]
+    #[serde(deserialize_with = "lenient::option_string")]
+    pub manufacturer: Option<String>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(default)]
+    #[serde(deserialize_with = "lenient::option_string")]
+    pub manufacturer_article_number: Option<String>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(default)]
+    #[serde(deserialize_with = "lenient::option_string")]
+    pub supplier_number: Option<String>,
+
+    #[serde(skip_serializing)]
+    #[serde(default)]
+    #[serde(deserialize_with = "lenient::option_string")]
+    pub supplier_name: Option<String>, // Read-only.
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(default)]
+    #[serde(deserialize_with = "lenient::option_boolean")]
+    pub webshop_article: Option<bool>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(default)]
+    #[serde(deserialize_with = "lenient::option_boolean")]
+    pub expired: Option<bool>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(default)]
+    #[serde(deserialize_with = "lenient::option_string")]
+    pub note: Option<String>,
+
+    // Fields that have no typed counterpart above, kept so that an article that
+    // is fetched, changed and sent back with `edit_article` does not lose them.
+    #[serde(flatten)]
+    #[serde(serialize_with = "serialize_unknown")]
+    pub unknown: HashMap<String, serde_json::Value>,
+}
+
+impl Article {
+    // Only the description is required; Fortnox assigns the next article
+    // number when it is left as None.
+    pub fn new(description: String) -> Article {
+        Article {
+            article_number: None,
+            description,
+            r#type: None,
+            active: None,
+            unit: None,
+            ean: None,
+            vat: None,
+            purchase_price: None,
+            sales_price: None,
+            sales_account: None,
+            eu_account: None,
+            eu_vat_account: None,
+            export_account: None,
+            purchase_account: None,
+            stock_goods: None,
+            quantity_in_stock: None,
+            reserved_quantity: None,
+            disposable_quantity: None,
+            stock_place: None,
+            stock_warning: None,
+            stock_value: None,
+            weight: None,
+            width: None,
+            height: None,
+            depth: None,
+            bulky: None,
+            manufacturer: None,
+            manufacturer_article_number: None,
+            supplier_number: None,
+            supplier_name: None,
+            webshop_article: None,
+            expired: None,
+            note: None,
+            unknown: HashMap::new(),
+        }
+    }
+
+    // A new article needs a description; an update needs its article number.
+    pub fn validate(&self, mode: ValidationMode) -> Result<(), Vec<FieldError>> {
+        let mut v = Validator::new(mode);
+        match v.mode() {
+            ValidationMode::Create => v.required("Description", Some(&self.description)),
+            ValidationMode::Update => v.required("ArticleNumber", self.article_number.as_deref()),
+        }
+        v.identifier("ArticleNumber", self.article_number.as_deref(), 50);
+        v.text("Description", Some(&self.description), 200);
+        v.text("Unit", self.unit.as_deref(), 50);
+        v.numeric("EAN", self.ean.as_deref(), 30);
+        v.text("StockPlace", self.stock_place.as_deref(), 100);
+        v.text("Manufacturer", self.manufacturer.as_deref(), 50);
+        v.text(
+            "ManufacturerArticleNumber",
+            self.manufacturer_article_number.as_deref(),
+            100,
+        );
+        v.identifier("SupplierNumber", self.supplier_number.as_deref(), 1024);
+        v.text("Note", self.note.as_deref(), 10000);
+        v.finish()
+    }
+}
+
+impl Resource for Article {
+    const PATH: &'static str = "articles";
+    const KEY: &'static str = "Article";
+    const LIST_KEY: &'static str = "Articles";
+    const ID_FIELD: &'static str = "ArticleNumber";
+    type ListItem = ListItem;
+
+    fn id(&self) -> Option<&str> {
+        self.article_number.as_deref()
+    }
+}
+
+// An article as it appears in a list, which only carries the most common fields.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "PascalCase")]
+pub struct ListItem {
+    #[serde(deserialize_with = "lenient::string")]
     pub article_number: String,
+
     pub description: String,
+
+    #[serde(default)]
+    #[serde(deserialize_with = "lenient::option_string")]
+    pub unit: Option<String>,
+
+    #[serde(default)]
+    #[serde(deserialize_with = "lenient::option")]
+    pub sales_price: Option<Amount>,
+
+    #[serde(default)]
+    #[serde(deserialize_with = "lenient::option")]
+    pub purchase_price: Option<Amount>,
+
+    #[serde(default)]
+    #[serde(deserialize_with = "lenient::option")]
+    pub quantity_in_stock: Option<Quantity>,
 }
@@ -0,0 +1,22 @@
+use crate::{Error, Gateway, Invoice};
+use serde::{Deserialize, Serialize};
+
+impl Gateway {
+    pub async fn cancel_invoice(&self, document_number: &str) -> Result<Invoice, Error> {
+        #[derive(Serialize)]
+        #[serde(rename_all = "PascalCase")]
+        struct Request {}
+
+        let body = Request {};
+
+        #[derive(Deserialize)]
+        #[serde(rename_all = "PascalCase")]
+        struct Response {
+            invoice: Invoice,
+        }
+
+        let url = self.action_url("invoices", document_number, "cancel")?;
+        let res: Response = self.http_put(&url, &body).await?;
+        Ok(res.invoice)
+    }
+}
@@ -0,0 +1,22 @@
+use crate::{Error, Gateway, Offer};
+use serde::{Deserialize, Serialize};
+
+impl Gateway {
+    pub async fn cancel_offer(&self, document_number: &str) -> Result<Offer, Error> {
+        #[derive(Serialize)]
+        #[serde(rename_all = "PascalCase")]
+        struct Request {}
+
+        let body = Request {};
+
+        #[derive(Deserialize)]
+        #[serde(rename_all = "PascalCase")]
+        struct Response {
+            offer: Offer,
+        }
+
+        let url = self.action_url("offers", document_number, "cancel")?;
+        let res: Response = self.http_put(&url, &body).await?;
+        Ok(res.offer)
+    }
+}
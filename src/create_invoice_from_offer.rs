@@ -0,0 +1,23 @@
+use crate::{Error, Gateway, Invoice};
+use serde::{Deserialize, Serialize};
+
+impl Gateway {
+    // Turns the offer into an invoice and returns the invoice.
+    pub async fn create_invoice_from_offer(&self, document_number: &str) -> Result<Invoice, Error> {
+        #[derive(Serialize)]
+        #[serde(rename_all = "PascalCase")]
+        struct Request {}
+
+        let body = Request {};
+
+        #[derive(Deserialize)]
+        #[serde(rename_all = "PascalCase")]
+        struct Response {
+            invoice: Invoice,
+        }
+
+        let url = self.action_url("offers", document_number, "createinvoice")?;
+        let res: Response = self.http_put(&url, &body).await?;
+        Ok(res.invoice)
+    }
+}
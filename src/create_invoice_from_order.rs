@@ -0,0 +1,24 @@
+use crate::{Error, Gateway, Invoice};
+use serde::{Deserialize, Serialize};
+
+impl Gateway {
+    // Turns the order into an invoice and returns the invoice. The order keeps a
+    // reference to it and can no longer be edited.
+    pub async fn create_invoice_from_order(&self, document_number: &str) -> Result<Invoice, Error> {
+        #[derive(Serialize)]
+        #[serde(rename_all = "PascalCase")]
+        struct Request {}
+
+        let body = Request {};
+
+        #[derive(Deserialize)]
+        #[serde(rename_all = "PascalCase")]
+        struct Response {
+            invoice: Invoice,
+        }
+
+        let url = self.action_url("orders", document_number, "createinvoice")?;
+        let res: Response = self.http_put(&url, &body).await?;
+        Ok(res.invoice)
+    }
+}
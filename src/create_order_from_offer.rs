@@ -0,0 +1,23 @@
+use crate::{Error, Gateway, Order};
+use serde::{Deserialize, Serialize};
+
+impl Gateway {
+    // Turns the offer into an order and returns the order.
+    pub async fn create_order_from_offer(&self, document_number: &str) -> Result<Order, Error> {
+        #[derive(Serialize)]
+        #[serde(rename_all = "PascalCase")]
+        struct Request {}
+
+        let body = Request {};
+
+        #[derive(Deserialize)]
+        #[serde(rename_all = "PascalCase")]
+        struct Response {
+            order: Order,
+        }
+
+        let url = self.action_url("offers", document_number, "createorder")?;
+        let res: Response = self.http_put(&url, &body).await?;
+        Ok(res.order)
+    }
+}
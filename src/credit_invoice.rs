@@ -0,0 +1,24 @@
+use crate::{Error, Gateway, Invoice};
+use serde::{Deserialize, Serialize};
+
+impl Gateway {
+    // Creates a credit invoice for the invoice. The returned invoice is the
+    // original, with `credit_invoice_reference` pointing at the new one.
+    pub async fn credit_invoice(&self, document_number: &str) -> Result<Invoice, Error> {
+        #[derive(Serialize)]
+        #[serde(rename_all = "PascalCase")]
+        struct Request {}
+
+        let body = Request {};
+
+        #[derive(Deserialize)]
+        #[serde(rename_all = "PascalCase")]
+        struct Response {
+            invoice: Invoice,
+        }
+
+        let url = self.action_url("invoices", document_number, "credit")?;
+        let res: Response = self.http_put(&url, &body).await?;
+        Ok(res.invoice)
+    }
+}
@@ -1,11 +1,97 @@
-use serde::{Deserialize, Serialize};
+use serde::de::{Deserialize, Deserializer};
+use serde::ser::{Serialize, Serializer};
+use std::fmt;
 
-#[derive(Serialize, Deserialize, Debug, Clone, Copy, Eq, PartialEq)]
-#[serde(rename_all = "UPPERCASE")]
-pub enum Currency {
-    NOK,
+// Defines `Currency` with a variant per listed ISO 4217 code, plus `Other` for
+// the codes that are not listed, since a Fortnox company can add any currency
+// to its register.
+macro_rules! currencies {
+    ($($name:ident,)*) => {
+        #[derive(Debug, Clone, Eq, PartialEq, Hash)]
+        pub enum Currency {
+            $($name,)*
+            Other(String),
+        }
+
+        impl Currency {
+            pub fn from_code(code: &str) -> Currency {
+                match code {
+                    $(stringify!($name) => Currency::$name,)*
+                    code => Currency::Other(code.to_string()),
+                }
+            }
+
+            pub fn code(&self) -> &str {
+                match self {
+                    $(Currency::$name => stringify!($name),)*
+                    Currency::Other(code) => code,
+                }
+            }
+        }
+    };
+}
+
+currencies! {
     SEK,
+    EUR,
+    USD,
+    GBP,
+    NOK,
     DKK,
     ISK,
-    GBP,
+    CHF,
+    PLN,
+    CZK,
+    HUF,
+    JPY,
+    CNY,
+    CAD,
+    AUD,
+    NZD,
+}
+
+impl fmt::Display for Currency {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(self.code())
+    }
+}
+
+impl Serialize for Currency {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(self.code())
+    }
+}
+
+// Never fails on a code that is not listed, see `Currency::Other`.
+impl<'de> Deserialize<'de> for Currency {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Currency, D::Error> {
+        let code = String::deserialize(deserializer)?;
+        Ok(Currency::from_code(&code.trim().to_uppercase()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn round_trips_listed_and_other_codes() {
+        for code in &["SEK", "EUR", "USD", "XYZ"] {
+            let json = format!("\"{}\"", code);
+            let currency: Currency = serde_json::from_str(&json).unwrap();
+            assert_eq!(currency.code(), *code);
+            assert_eq!(serde_json::to_string(&currency).unwrap(), json);
+        }
+        assert_eq!(Currency::from_code("EUR"), Currency::EUR);
+        assert_eq!(
+            Currency::from_code("XYZ"),
+            Currency::Other("XYZ".to_string())
+        );
+    }
+
+    #[test]
+    fn ignores_case_and_whitespace() {
+        let currency: Currency = serde_json::from_str("\" eur \"").unwrap();
+        assert_eq!(currency, Currency::EUR);
+    }
 }
@@ -1,35 +1,545 @@
+use crate::order::serialize_unknown;
+use crate::validation::Validator;
+use crate::{
+    lenient, Amount, Currency, CustomerType, FieldError, Resource, ValidationMode, VatType,
+};
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
 #[serde(rename_all = "PascalCase")]
 pub struct Customer {
     #[serde(skip_serializing_if = "Option::is_none")]
     #[serde(default)]
+    #[serde(deserialize_with = "lenient::option_string")]
     pub customer_number: Option<String>,
 
     pub name: String,
 
-    pub address1: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(default)]
+    #[serde(deserialize_with = "lenient::option")]
+    pub r#type: Option<CustomerType>,
+
+    // Or personal number for private customers.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(default)]
+    #[serde(deserialize_with = "lenient::option_string")]
+    pub organisation_number: Option<String>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(rename = "VATNumber")]
+    #[serde(default)]
+    #[serde(deserialize_with = "lenient::option_string")]
+    pub vat_number: Option<String>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(rename = "VATType")]
+    #[serde(default)]
+    #[serde(deserialize_with = "lenient::option")]
+    pub vat_type: Option<VatType>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(default)]
+    #[serde(deserialize_with = "lenient::option_string")]
+    pub address1: Option<String>,
 
     #[serde(skip_serializing_if = "Option::is_none")]
     #[serde(default)]
+    #[serde(deserialize_with = "lenient::option_string")]
     pub address2: Option<String>,
 
-    pub zip_code: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(default)]
+    #[serde(deserialize_with = "lenient::option_string")]
+    pub zip_code: Option<String>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(default)]
+    #[serde(deserialize_with = "lenient::option_string")]
+    pub city: Option<String>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(default)]
+    #[serde(deserialize_with = "lenient::option_string")]
+    pub country: Option<String>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(default)]
+    #[serde(deserialize_with = "lenient::option_string")]
+    pub country_code: Option<String>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(default)]
+    #[serde(deserialize_with = "lenient::option_string")]
+    pub delivery_name: Option<String>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(default)]
+    #[serde(deserialize_with = "lenient::option_string")]
+    pub delivery_address1: Option<String>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(default)]
+    #[serde(deserialize_with = "lenient::option_string")]
+    pub delivery_address2: Option<String>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(default)]
+    #[serde(deserialize_with = "lenient::option_string")]
+    pub delivery_zip_code: Option<String>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(default)]
+    #[serde(deserialize_with = "lenient::option_string")]
+    pub delivery_city: Option<String>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(default)]
+    #[serde(deserialize_with = "lenient::option_string")]
+    pub delivery_country: Option<String>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(default)]
+    #[serde(deserialize_with = "lenient::option_string")]
+    pub delivery_country_code: Option<String>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(default)]
+    #[serde(deserialize_with = "lenient::option_string")]
+    pub delivery_phone1: Option<String>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(default)]
+    #[serde(deserialize_with = "lenient::option_string")]
+    pub delivery_phone2: Option<String>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(default)]
+    #[serde(deserialize_with = "lenient::option_string")]
+    pub delivery_fax: Option<String>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(default)]
+    #[serde(deserialize_with = "lenient::option_string")]
+    pub visiting_address: Option<String>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(default)]
+    #[serde(deserialize_with = "lenient::option_string")]
+    pub visiting_zip_code: Option<String>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(default)]
+    #[serde(deserialize_with = "lenient::option_string")]
+    pub visiting_city: Option<String>,
 
-    pub city: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(default)]
+    #[serde(deserialize_with = "lenient::option_string")]
+    pub visiting_country: Option<String>,
 
-    pub country_code: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(default)]
+    #[serde(deserialize_with = "lenient::option_string")]
+    pub visiting_country_code: Option<String>,
 
     #[serde(skip_serializing_if = "Option::is_none")]
     #[serde(default)]
+    #[serde(deserialize_with = "lenient::option_string")]
     pub comments: Option<String>,
 
     #[serde(skip_serializing_if = "Option::is_none")]
     #[serde(default)]
+    #[serde(deserialize_with = "lenient::option_string")]
     pub email: Option<String>,
 
     #[serde(skip_serializing_if = "Option::is_none")]
     #[serde(default)]
+    #[serde(deserialize_with = "lenient::option_string")]
     pub phone1: Option<String>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(default)]
+    #[serde(deserialize_with = "lenient::option_string")]
+    pub phone2: Option<String>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(default)]
+    #[serde(deserialize_with = "lenient::option_string")]
+    pub fax: Option<String>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(rename = "WWW")]
+    #[serde(default)]
+    #[serde(deserialize_with = "lenient::option_string")]
+    pub www: Option<String>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(default)]
+    #[serde(deserialize_with = "lenient::option_string")]
+    pub our_reference: Option<String>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(default)]
+    #[serde(deserialize_with = "lenient::option_string")]
+    pub your_reference: Option<String>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(default)]
+    #[serde(deserialize_with = "lenient::option_string")]
+    pub external_reference: Option<String>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(default)]
+    #[serde(deserialize_with = "lenient::option_string")]
+    pub terms_of_delivery: Option<String>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(default)]
+    #[serde(deserialize_with = "lenient::option_string")]
+    pub terms_of_payment: Option<String>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(default)]
+    #[serde(deserialize_with = "lenient::option_string")]
+    pub way_of_delivery: Option<String>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(default)]
+    #[serde(deserialize_with = "lenient::option_string")]
+    pub price_list: Option<String>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(default)]
+    #[serde(deserialize_with = "lenient::option")]
+    pub currency: Option<Currency>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(default)]
+    #[serde(deserialize_with = "lenient::option_string")]
+    pub cost_center: Option<String>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(default)]
+    #[serde(deserialize_with = "lenient::option_string")]
+    pub project: Option<String>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(default)]
+    #[serde(deserialize_with = "lenient::option_string")]
+    pub sales_account: Option<String>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(rename = "ShowPriceVATIncluded")]
+    #[serde(default)]
+    #[serde(deserialize_with = "lenient::option_boolean")]
+    pub show_price_vat_included: Option<bool>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(default)]
+    #[serde(deserialize_with = "lenient::option")]
+    pub invoice_administration_fee: Option<Amount>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(default)]
+    #[serde(deserialize_with = "lenient::option")]
+    pub invoice_freight: Option<Amount>,
+
+    // In percent.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(default)]
+    #[serde(deserialize_with = "lenient::option")]
+    pub invoice_discount: Option<Amount>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(default)]
+    #[serde(deserialize_with = "lenient::option_string")]
+    pub invoice_remark: Option<String>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(default)]
+    #[serde(deserialize_with = "lenient::option_string")]
+    pub email_invoice: Option<String>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(rename = "EmailInvoiceCC")]
+    #[serde(default)]
+    #[serde(deserialize_with = "lenient::option_string")]
+    pub email_invoice_cc: Option<String>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(rename = "EmailInvoiceBCC")]
+    #[serde(default)]
+    #[serde(deserialize_with = "lenient::option_string")]
+    pub email_invoice_bcc: Option<String>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(default)]
+    #[serde(deserialize_with = "lenient::option_string")]
+    pub email_order: Option<String>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(rename = "EmailOrderCC")]
+    #[serde(default)]
+    #[serde(deserialize_with = "lenient::option_string")]
+    pub email_order_cc: Option<String>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(rename = "EmailOrderBCC")]
+    #[serde(default)]
+    #[serde(deserialize_with = "lenient::option_string")]
+    pub email_order_bcc: Option<String>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(default)]
+    #[serde(deserialize_with = "lenient::option_string")]
+    pub email_offer: Option<String>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(rename = "EmailOfferCC")]
+    #[serde(default)]
+    #[serde(deserialize_with = "lenient::option_string")]
+    pub email_offer_cc: Option<String>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(rename = "EmailOfferBCC")]
+    #[serde(default)]
+    #[serde(deserialize_with = "lenient::option_string")]
+    pub email_offer_bcc: Option<String>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(rename = "EInvoice")]
+    #[serde(default)]
+    #[serde(deserialize_with = "lenient::option_boolean")]
+    pub e_invoice: Option<bool>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(rename = "GLN")]
+    #[serde(default)]
+    #[serde(deserialize_with = "lenient::option_string")]
+    pub gln: Option<String>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(rename = "GLNDelivery")]
+    #[serde(default)]
+    #[serde(deserialize_with = "lenient::option_string")]
+    pub gln_delivery: Option<String>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(default)]
+    #[serde(deserialize_with = "lenient::option_boolean")]
+    pub active: Option<bool>,
+
+    // Fields that have no typed counterpart above, kept so that a customer that
+    // is fetched, changed and sent back with `edit_customer` does not lose them.
+    #[serde(flatten)]
+    #[serde(serialize_with = "serialize_unknown")]
+    pub unknown: HashMap<String, serde_json::Value>,
+}
+
+impl Customer {
+    // Only the name is required; Fortnox assigns the next customer number when
+    // it is left as None.
+    pub fn new(name: String) -> Customer {
+        Customer {
+            customer_number: None,
+            name,
+            r#type: None,
+            organisation_number: None,
+            vat_number: None,
+            vat_type: None,
+            address1: None,
+            address2: None,
+            zip_code: None,
+            city: None,
+            country: None,
+            country_code: None,
+            delivery_name: None,
+            delivery_address1: None,
+            delivery_address2: None,
+            delivery_zip_code: None,
+            delivery_city: None,
+            delivery_country: None,
+            delivery_country_code: None,
+            delivery_phone1: None,
+            delivery_phone2: None,
+            delivery_fax: None,
+            visiting_address: None,
+            visiting_zip_code: None,
+            visiting_city: None,
+            visiting_country: None,
+            visiting_country_code: None,
+            comments: None,
+            email: None,
+            phone1: None,
+            phone2: None,
+            fax: None,
+            www: None,
+            our_reference: None,
+            your_reference: None,
+            external_reference: None,
+            terms_of_delivery: None,
+            terms_of_payment: None,
+            way_of_delivery: None,
+            price_list: None,
+            currency: None,
+            cost_center: None,
+            project: None,
+            sales_account: None,
+            show_price_vat_included: None,
+            invoice_administration_fee: None,
+            invoice_freight: None,
+            invoice_discount: None,
+            invoice_remark: None,
+            email_invoice: None,
+            email_invoice_cc: None,
+            email_invoice_bcc: None,
+            email_order: None,
+            email_order_cc: None,
+            email_order_bcc: None,
+            email_offer: None,
+            email_offer_cc: None,
+            email_offer_bcc: None,
+            e_invoice: None,
+            gln: None,
+            gln_delivery: None,
+            active: None,
+            unknown: HashMap::new(),
+        }
+    }
+
+    // A new customer needs a name; an update needs its customer number.
+    pub fn validate(&self, mode: ValidationMode) -> Result<(), Vec<FieldError>> {
+        let mut v = Validator::new(mode);
+        match v.mode() {
+            ValidationMode::Create => v.required("Name", Some(&self.name)),
+            ValidationMode::Update => v.required("CustomerNumber", self.customer_number.as_deref()),
+        }
+        v.identifier("CustomerNumber", self.customer_number.as_deref(), 1024);
+        v.text("Name", Some(&self.name), 1024);
+        v.text(
+            "OrganisationNumber",
+            self.organisation_number.as_deref(),
+            30,
+        );
+        v.text("VATNumber", self.vat_number.as_deref(), 30);
+
+        v.text("Address1", self.address1.as_deref(), 1024);
+        v.text("Address2", self.address2.as_deref(), 1024);
+        v.text("ZipCode", self.zip_code.as_deref(), 10);
+        v.text("City", self.city.as_deref(), 1024);
+        v.text("Country", self.country.as_deref(), 1024);
+        v.country_code("CountryCode", self.country_code.as_deref());
+
+        v.text("DeliveryName", self.delivery_name.as_deref(), 1024);
+        v.text("DeliveryAddress1", self.delivery_address1.as_deref(), 1024);
+        v.text("DeliveryAddress2", self.delivery_address2.as_deref(), 1024);
+        v.text("DeliveryZipCode", self.delivery_zip_code.as_deref(), 10);
+        v.text("DeliveryCity", self.delivery_city.as_deref(), 1024);
+        v.text("DeliveryCountry", self.delivery_country.as_deref(), 1024);
+        v.country_code("DeliveryCountryCode", self.delivery_country_code.as_deref());
+        v.text("DeliveryPhone1", self.delivery_phone1.as_deref(), 1024);
+        v.text("DeliveryPhone2", self.delivery_phone2.as_deref(), 1024);
+        v.text("DeliveryFax", self.delivery_fax.as_deref(), 1024);
+
+        v.text("VisitingAddress", self.visiting_address.as_deref(), 128);
+        v.text("VisitingZipCode", self.visiting_zip_code.as_deref(), 10);
+        v.text("VisitingCity", self.visiting_city.as_deref(), 128);
+        v.text("VisitingCountry", self.visiting_country.as_deref(), 128);
+        v.country_code("VisitingCountryCode", self.visiting_country_code.as_deref());
+
+        v.text("Comments", self.comments.as_deref(), 1024);
+        v.emails("Email", self.email.as_deref(), 1024);
+        v.text("Phone1", self.phone1.as_deref(), 1024);
+        v.text("Phone2", self.phone2.as_deref(), 1024);
+        v.text("Fax", self.fax.as_deref(), 1024);
+        v.text("WWW", self.www.as_deref(), 128);
+        v.text("OurReference", self.our_reference.as_deref(), 50);
+        v.text("YourReference", self.your_reference.as_deref(), 50);
+        v.text("InvoiceRemark", self.invoice_remark.as_deref(), 1024);
+
+        v.emails("EmailInvoice", self.email_invoice.as_deref(), 1024);
+        v.emails("EmailInvoiceCC", self.email_invoice_cc.as_deref(), 1024);
+        v.emails("EmailInvoiceBCC", self.email_invoice_bcc.as_deref(), 1024);
+        v.emails("EmailOrder", self.email_order.as_deref(), 1024);
+        v.emails("EmailOrderCC", self.email_order_cc.as_deref(), 1024);
+        v.emails("EmailOrderBCC", self.email_order_bcc.as_deref(), 1024);
+        v.emails("EmailOffer", self.email_offer.as_deref(), 1024);
+        v.emails("EmailOfferCC", self.email_offer_cc.as_deref(), 1024);
+        v.emails("EmailOfferBCC", self.email_offer_bcc.as_deref(), 1024);
+
+        v.numeric("GLN", self.gln.as_deref(), 13);
+        v.numeric("GLNDelivery", self.gln_delivery.as_deref(), 13);
+        v.finish()
+    }
+}
+
+impl Resource for Customer {
+    const PATH: &'static str = "customers";
+    const KEY: &'static str = "Customer";
+    const LIST_KEY: &'static str = "Customers";
+    const ID_FIELD: &'static str = "CustomerNumber";
+    type ListItem = ListItem;
+
+    fn id(&self) -> Option<&str> {
+        self.customer_number.as_deref()
+    }
+}
+
+// A customer as it appears in a list, which only carries the most common fields.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "PascalCase")]
+pub struct ListItem {
+    #[serde(deserialize_with = "lenient::string")]
+    pub customer_number: String,
+
+    pub name: String,
+
+    #[serde(default)]
+    #[serde(deserialize_with = "lenient::option_string")]
+    pub address1: Option<String>,
+
+    #[serde(default)]
+    #[serde(deserialize_with = "lenient::option_string")]
+    pub address2: Option<String>,
+
+    #[serde(default)]
+    #[serde(deserialize_with = "lenient::option_string")]
+    pub zip_code: Option<String>,
+
+    #[serde(default)]
+    #[serde(deserialize_with = "lenient::option_string")]
+    pub city: Option<String>,
+
+    #[serde(default)]
+    #[serde(deserialize_with = "lenient::option_string")]
+    pub email: Option<String>,
+
+    #[serde(default)]
+    #[serde(deserialize_with = "lenient::option_string")]
+    pub phone: Option<String>,
+
+    #[serde(default)]
+    #[serde(deserialize_with = "lenient::option_string")]
+    pub organisation_number: Option<String>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn keeps_any_currency() {
+        let customer: Customer =
+            serde_json::from_str(r#"{"Name":"Acme","Currency":"EUR"}"#).unwrap();
+        assert_eq!(customer.currency, Some(Currency::EUR));
+
+        let customer: Customer =
+            serde_json::from_str(r#"{"Name":"Acme","Currency":"XYZ"}"#).unwrap();
+        assert_eq!(customer.currency, Some(Currency::Other("XYZ".to_string())));
+
+        let customer: Customer = serde_json::from_str(r#"{"Name":"Acme","Currency":""}"#).unwrap();
+        assert_eq!(customer.currency, None);
+    }
 }
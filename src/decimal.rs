@@ -0,0 +1,659 @@
+use serde::de::{self, Deserialize, Deserializer, Visitor};
+use serde::ser::{Serialize, Serializer};
+use std::cmp::Ordering;
+use std::convert::TryFrom;
+use std::fmt;
+use std::iter::Sum;
+use std::ops::{Add, AddAssign, Mul, Neg, Sub, SubAssign};
+use std::str::FromStr;
+
+// The most decimals a value can have, the same as rust_decimal allows.
+const MAX_SCALE: u32 = 28;
+
+// An exact decimal number, `mantissa / 10^scale`. Always normalized, i.e.
+// without trailing zeros in the mantissa, so that equal values compare equal,
+// and never with more than `MAX_SCALE` decimals.
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Default)]
+struct Decimal {
+    mantissa: i128,
+    scale: u32,
+}
+
+impl Decimal {
+    fn new(mantissa: i128, scale: u32) -> Decimal {
+        let mut d = Decimal { mantissa, scale };
+        while d.scale > 0 && d.mantissa % 10 == 0 {
+            d.mantissa /= 10;
+            d.scale -= 1;
+        }
+        if d.mantissa == 0 {
+            d.scale = 0;
+        }
+        d
+    }
+
+    // Both mantissas at the larger of the two scales, or None if either does
+    // not fit.
+    fn aligned(self, other: Decimal) -> Option<(i128, i128, u32)> {
+        let scale = self.scale.max(other.scale);
+        Some((
+            rescale(self.mantissa, scale - self.scale)?,
+            rescale(other.mantissa, scale - other.scale)?,
+            scale,
+        ))
+    }
+
+    // The integer part and the fraction, the latter as a mantissa at
+    // `MAX_SCALE`. Both fit whatever the value, and both have its sign.
+    fn split(self) -> (i128, i128) {
+        let factor = 10i128.pow(self.scale);
+        (
+            self.mantissa / factor,
+            self.mantissa % factor * 10i128.pow(MAX_SCALE - self.scale),
+        )
+    }
+
+    fn checked_add(self, other: Decimal) -> Option<Decimal> {
+        let (a, b, scale) = self.aligned(other)?;
+        Some(Decimal::new(a.checked_add(b)?, scale))
+    }
+
+    fn checked_mul(self, other: Decimal) -> Option<Decimal> {
+        let mantissa = self.mantissa.checked_mul(other.mantissa)?;
+        Some(Decimal::new(mantissa, self.scale + other.scale).round(MAX_SCALE))
+    }
+
+    fn round(self, decimals: u32) -> Decimal {
+        if self.scale <= decimals {
+            return self;
+        }
+
+        // Half away from zero, as in accounting. A divisor too large for an
+        // i128 is larger than any mantissa, so the value rounds to zero.
+        let divisor = match 10i128.checked_pow(self.scale - decimals) {
+            Some(divisor) => divisor,
+            None => return Decimal::default(),
+        };
+        let quotient = self.mantissa / divisor;
+        let remainder = self.mantissa % divisor;
+        let rounded = if remainder.unsigned_abs() * 2 >= divisor.unsigned_abs() {
+            quotient + self.mantissa.signum()
+        } else {
+            quotient
+        };
+        Decimal::new(rounded, decimals)
+    }
+
+    fn to_f64(self) -> f64 {
+        // Parsing the exact decimal text gives the closest f64.
+        self.to_string().parse().unwrap_or(0.0)
+    }
+}
+
+fn rescale(mantissa: i128, by: u32) -> Option<i128> {
+    10i128
+        .checked_pow(by)
+        .and_then(|factor| mantissa.checked_mul(factor))
+}
+
+impl Add for Decimal {
+    type Output = Decimal;
+
+    fn add(self, other: Decimal) -> Decimal {
+        match self.checked_add(other) {
+            Some(sum) => sum,
+            None => panic!("decimal overflow"),
+        }
+    }
+}
+
+impl Mul for Decimal {
+    type Output = Decimal;
+
+    fn mul(self, other: Decimal) -> Decimal {
+        match self.checked_mul(other) {
+            Some(product) => product,
+            None => panic!("decimal overflow"),
+        }
+    }
+}
+
+impl Neg for Decimal {
+    type Output = Decimal;
+
+    fn neg(self) -> Decimal {
+        Decimal::new(-self.mantissa, self.scale)
+    }
+}
+
+// Compares the integer parts first, since aligning the scales of a large and a
+// very precise value could overflow.
+impl Ord for Decimal {
+    fn cmp(&self, other: &Decimal) -> Ordering {
+        self.split().cmp(&other.split())
+    }
+}
+
+impl PartialOrd for Decimal {
+    fn partial_cmp(&self, other: &Decimal) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl fmt::Display for Decimal {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let digits = self.mantissa.unsigned_abs().to_string();
+        let sign = if self.mantissa < 0 { "-" } else { "" };
+        let scale = self.scale as usize;
+        if scale == 0 {
+            return write!(f, "{}{}", sign, digits);
+        }
+
+        let digits = format!("{:0>width$}", digits, width = scale + 1);
+        let (int, frac) = digits.split_at(digits.len() - scale);
+        write!(f, "{}{}.{}", sign, int, frac)
+    }
+}
+
+impl FromStr for Decimal {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Decimal, String> {
+        let invalid = || format!("Invalid decimal number \"{}\".", s);
+
+        let trimmed = s.trim();
+        let (negative, unsigned) = match trimmed.strip_prefix('-') {
+            Some(rest) => (true, rest),
+            None => (false, trimmed.strip_prefix('+').unwrap_or(trimmed)),
+        };
+        let (int, frac) = match unsigned.find('.') {
+            Some(i) => (&unsigned[..i], &unsigned[i + 1..]),
+            None => (unsigned, ""),
+        };
+        if int.is_empty() && frac.is_empty()
+            || !int.chars().chain(frac.chars()).all(|c| c.is_ascii_digit())
+        {
+            return Err(invalid());
+        }
+
+        // Trailing zeros carry no value, and dropping them keeps long but
+        // exact values such as "1.50000000000000000000000000000" in range.
+        let frac = frac.trim_end_matches('0');
+        if frac.len() > MAX_SCALE as usize {
+            return Err(invalid());
+        }
+
+        let mut mantissa: i128 = 0;
+        for c in int.chars().chain(frac.chars()) {
+            mantissa = match mantissa
+                .checked_mul(10)
+                .and_then(|m| m.checked_add(i128::from(c as u8 - b'0')))
+            {
+                Some(m) => m,
+                None => return Err(invalid()),
+            };
+        }
+        if negative {
+            mantissa = -mantissa;
+        }
+        Ok(Decimal::new(mantissa, frac.len() as u32))
+    }
+}
+
+struct DecimalVisitor;
+
+impl<'de> Visitor<'de> for DecimalVisitor {
+    type Value = Decimal;
+
+    fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "a decimal number, either as a number or as a string")
+    }
+
+    fn visit_i64<E: de::Error>(self, v: i64) -> Result<Decimal, E> {
+        Ok(Decimal::new(i128::from(v), 0))
+    }
+
+    fn visit_u64<E: de::Error>(self, v: u64) -> Result<Decimal, E> {
+        Ok(Decimal::new(i128::from(v), 0))
+    }
+
+    fn visit_f64<E: de::Error>(self, v: f64) -> Result<Decimal, E> {
+        decimal_from_f64(v).map_err(E::custom)
+    }
+
+    fn visit_str<E: de::Error>(self, v: &str) -> Result<Decimal, E> {
+        v.parse().map_err(E::custom)
+    }
+}
+
+fn decimal_from_f64(v: f64) -> Result<Decimal, String> {
+    if !v.is_finite() {
+        return Err(format!("Invalid decimal number \"{}\".", v));
+    }
+    // Display gives the shortest text that reads back as the same f64, which
+    // is what the number looked like in the JSON.
+    v.to_string().parse()
+}
+
+macro_rules! decimal_type {
+    ($name:ident) => {
+        // Arithmetic with the operators panics if the result does not fit,
+        // which takes values far beyond any real amount; use the `checked_`
+        // methods where that cannot be ruled out.
+        #[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Default)]
+        pub struct $name(Decimal);
+
+        impl $name {
+            // `mantissa / 10^scale`, e.g. `new(1250, 2)` is 12.50. Rounded to
+            // 28 decimals if `scale` is larger.
+            pub fn new(mantissa: i64, scale: u32) -> $name {
+                $name(Decimal::new(i128::from(mantissa), scale).round(MAX_SCALE))
+            }
+
+            pub fn zero() -> $name {
+                $name(Decimal::default())
+            }
+
+            pub fn is_zero(&self) -> bool {
+                self.0.mantissa == 0
+            }
+
+            pub fn is_negative(&self) -> bool {
+                self.0.mantissa < 0
+            }
+
+            // The number of decimals, without trailing zeros.
+            pub fn scale(&self) -> u32 {
+                self.0.scale
+            }
+
+            // Rounded half away from zero to at most `decimals` decimals.
+            pub fn round(&self, decimals: u32) -> $name {
+                $name(self.0.round(decimals))
+            }
+
+            pub fn to_f64(&self) -> f64 {
+                self.0.to_f64()
+            }
+
+            // None if the sum does not fit.
+            pub fn checked_add(self, other: $name) -> Option<$name> {
+                self.0.checked_add(other.0).map($name)
+            }
+
+            // None if the difference does not fit.
+            pub fn checked_sub(self, other: $name) -> Option<$name> {
+                let negated = Decimal::new(other.0.mantissa.checked_neg()?, other.0.scale);
+                self.0.checked_add(negated).map($name)
+            }
+        }
+
+        impl fmt::Display for $name {
+            fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+                self.0.fmt(f)
+            }
+        }
+
+        impl fmt::Debug for $name {
+            fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+                write!(f, "{}({})", stringify!($name), self.0)
+            }
+        }
+
+        impl FromStr for $name {
+            type Err = crate::Error;
+
+            fn from_str(s: &str) -> Result<$name, crate::Error> {
+                match s.parse() {
+                    Ok(d) => Ok($name(d)),
+                    Err(err) => Err(crate::Error::parse(err)),
+                }
+            }
+        }
+
+        impl From<i64> for $name {
+            fn from(v: i64) -> $name {
+                $name::new(v, 0)
+            }
+        }
+
+        impl From<i32> for $name {
+            fn from(v: i32) -> $name {
+                $name::new(i64::from(v), 0)
+            }
+        }
+
+        impl From<u32> for $name {
+            fn from(v: u32) -> $name {
+                $name::new(i64::from(v), 0)
+            }
+        }
+
+        // Takes the shortest decimal that reads back as the same f64, e.g. 0.1
+        // becomes exactly 0.1.
+        impl TryFrom<f64> for $name {
+            type Error = crate::Error;
+
+            fn try_from(v: f64) -> Result<$name, crate::Error> {
+                match decimal_from_f64(v) {
+                    Ok(d) => Ok($name(d)),
+                    Err(err) => Err(crate::Error::parse(err)),
+                }
+            }
+        }
+
+        impl Add for $name {
+            type Output = $name;
+
+            fn add(self, other: $name) -> $name {
+                $name(self.0 + other.0)
+            }
+        }
+
+        impl AddAssign for $name {
+            fn add_assign(&mut self, other: $name) {
+                *self = *self + other;
+            }
+        }
+
+        impl Sub for $name {
+            type Output = $name;
+
+            fn sub(self, other: $name) -> $name {
+                $name(self.0 + -other.0)
+            }
+        }
+
+        impl SubAssign for $name {
+            fn sub_assign(&mut self, other: $name) {
+                *self = *self - other;
+            }
+        }
+
+        impl Neg for $name {
+            type Output = $name;
+
+            fn neg(self) -> $name {
+                $name(-self.0)
+            }
+        }
+
+        impl Sum for $name {
+            fn sum<I: Iterator<Item = $name>>(iter: I) -> $name {
+                iter.fold($name::zero(), |sum, v| sum + v)
+            }
+        }
+
+        impl<'a> Sum<&'a $name> for $name {
+            fn sum<I: Iterator<Item = &'a $name>>(iter: I) -> $name {
+                iter.fold($name::zero(), |sum, v| sum + *v)
+            }
+        }
+
+        // Fortnox sends these as numbers in some places and as strings in others.
+        impl<'de> Deserialize<'de> for $name {
+            fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<$name, D::Error> {
+                deserializer.deserialize_any(DecimalVisitor).map($name)
+            }
+        }
+
+        #[cfg(feature = "rust_decimal")]
+        impl From<rust_decimal::Decimal> for $name {
+            fn from(v: rust_decimal::Decimal) -> $name {
+                $name(Decimal::new(v.mantissa(), v.scale()))
+            }
+        }
+
+        #[cfg(feature = "rust_decimal")]
+        impl TryFrom<$name> for rust_decimal::Decimal {
+            type Error = crate::Error;
+
+            fn try_from(v: $name) -> Result<rust_decimal::Decimal, crate::Error> {
+                match rust_decimal::Decimal::try_from_i128_with_scale(v.0.mantissa, v.0.scale) {
+                    Ok(d) => Ok(d),
+                    Err(err) => Err(crate::Error::parse(format!(
+                        "{} does not fit in a rust_decimal::Decimal ({}).",
+                        v, err
+                    ))
+                    .with_source(err)),
+                }
+            }
+        }
+    };
+}
+
+decimal_type!(Amount);
+decimal_type!(Quantity);
+
+// Fortnox takes amounts as JSON numbers.
+impl Serialize for Amount {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        match i64::try_from(self.0.mantissa) {
+            Ok(v) if self.0.scale == 0 => serializer.serialize_i64(v),
+            _ => serializer.serialize_f64(self.0.to_f64()),
+        }
+    }
+}
+
+// Fortnox takes quantities as strings, which keeps them exact.
+impl Serialize for Quantity {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(&self.0)
+    }
+}
+
+// A unit price times a quantity.
+impl Mul<Quantity> for Amount {
+    type Output = Amount;
+
+    fn mul(self, quantity: Quantity) -> Amount {
+        Amount(self.0 * quantity.0)
+    }
+}
+
+impl Mul<Amount> for Quantity {
+    type Output = Amount;
+
+    fn mul(self, amount: Amount) -> Amount {
+        Amount(self.0 * amount.0)
+    }
+}
+
+impl Amount {
+    // None if the product does not fit.
+    pub fn checked_mul(self, quantity: Quantity) -> Option<Amount> {
+        self.0.checked_mul(quantity.0).map(Amount)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn amount(s: &str) -> Amount {
+        s.parse().unwrap()
+    }
+
+    #[test]
+    fn parses_signs() {
+        assert_eq!(amount("-1.5"), Amount::new(-15, 1));
+        assert_eq!(amount("+1.5"), Amount::new(15, 1));
+        assert_eq!(amount(" 2 "), Amount::new(2, 0));
+        assert!("--1".parse::<Amount>().is_err());
+        assert!("-".parse::<Amount>().is_err());
+    }
+
+    #[test]
+    fn parses_leading_and_trailing_dots() {
+        assert_eq!(amount(".5"), Amount::new(5, 1));
+        assert_eq!(amount("-.5"), Amount::new(-5, 1));
+        assert_eq!(amount("1."), Amount::new(1, 0));
+        assert!(".".parse::<Amount>().is_err());
+        assert!("1.2.3".parse::<Amount>().is_err());
+        assert!("1,5".parse::<Amount>().is_err());
+    }
+
+    #[test]
+    fn parses_at_most_28_decimals() {
+        let max = format!("0.{}1", "0".repeat(27));
+        assert_eq!(amount(&max).scale(), 28);
+
+        let too_many = format!("0.{}1", "0".repeat(28));
+        assert!(too_many.parse::<Amount>().is_err());
+
+        // Trailing zeros do not count.
+        assert_eq!(
+            amount("1.50000000000000000000000000000"),
+            Amount::new(15, 1)
+        );
+    }
+
+    #[test]
+    fn rejects_overflow() {
+        let max = i128::MAX.to_string();
+        assert_eq!(amount(&max).to_string(), max);
+        assert!(format!("{}0", max).parse::<Amount>().is_err());
+        assert!(format!("1{}", max).parse::<Amount>().is_err());
+    }
+
+    #[test]
+    fn normalizes() {
+        assert_eq!(amount("12.500"), amount("12.5"));
+        assert_eq!(amount("12.500").to_string(), "12.5");
+        assert_eq!(amount("-0.0"), Amount::zero());
+        assert_eq!(amount("-0.0").to_string(), "0");
+        assert_eq!(amount("0.05").to_string(), "0.05");
+    }
+
+    #[test]
+    fn rounds_half_away_from_zero() {
+        assert_eq!(amount("2.5").round(0), amount("3"));
+        assert_eq!(amount("-2.5").round(0), amount("-3"));
+        assert_eq!(amount("1.005").round(2), amount("1.01"));
+        assert_eq!(amount("-1.005").round(2), amount("-1.01"));
+        assert_eq!(amount("1.0049").round(2), amount("1"));
+        assert_eq!(amount("-1.0049").round(2), amount("-1"));
+        assert_eq!(amount("0.4").round(0), Amount::zero());
+        assert_eq!(amount("1.25").round(5), amount("1.25"));
+    }
+
+    #[test]
+    fn multiplies() {
+        let price = amount("19.90");
+        let quantity: Quantity = "2.5".parse().unwrap();
+        assert_eq!(price * quantity, amount("49.75"));
+        assert_eq!(quantity * price, amount("49.75"));
+        assert_eq!((price * quantity).scale(), 2);
+
+        // The product is normalized, so 1.25 * 2 has a single decimal.
+        let two: Quantity = "2".parse().unwrap();
+        assert_eq!((amount("1.25") * two).to_string(), "2.5");
+    }
+
+    #[test]
+    fn multiplying_rounds_to_28_decimals() {
+        // 1.5e-14 * 1e-14 = 1.5e-28, which does not fit and rounds up.
+        let product = Amount::new(15, 15) * Quantity::new(1, 14);
+        assert_eq!(product.scale(), 28);
+        assert_eq!(product, Amount::new(2, 28));
+
+        let product = Amount::new(-15, 15) * Quantity::new(1, 14);
+        assert_eq!(product, Amount::new(-2, 28));
+    }
+
+    #[test]
+    fn compares_without_overflowing() {
+        let large = amount("100000000000000000000");
+        let precise = amount("0.0000000000000000000000000001");
+        assert!(precise < large);
+        assert!(-large < precise);
+        assert!(-large < -precise);
+
+        let max = amount(&i128::MAX.to_string());
+        assert!(precise < max);
+        assert!(-max < -precise);
+    }
+
+    #[test]
+    fn compares_by_value() {
+        let mut values = [
+            amount("1.5"),
+            amount("-1.2"),
+            amount("-1.5"),
+            amount("0.2"),
+            amount("-0.5"),
+            amount("1.25"),
+            amount("0"),
+            amount("1"),
+        ];
+        values.sort();
+        let sorted: Vec<String> = values.iter().map(|v| v.to_string()).collect();
+        assert_eq!(
+            sorted,
+            vec!["-1.5", "-1.2", "-0.5", "0", "0.2", "1", "1.25", "1.5"]
+        );
+        assert_eq!(amount("1.50").cmp(&amount("1.5")), Ordering::Equal);
+    }
+
+    #[test]
+    fn checked_arithmetic_reports_overflow() {
+        let max = amount(&i128::MAX.to_string());
+        assert_eq!(max.checked_add(amount("1")), None);
+        assert_eq!(amount("1").checked_add(amount("0.5")), Some(amount("1.5")));
+        assert_eq!((-max).checked_sub(amount("2")), None);
+        assert_eq!(amount("1").checked_sub(amount("0.5")), Some(amount("0.5")));
+
+        // Aligning the scales overflows too.
+        let large = amount("100000000000000000000");
+        let precise = amount("0.0000000000000000000000000001");
+        assert_eq!(large.checked_add(precise), None);
+
+        let quantity: Quantity = i128::MAX.to_string().parse().unwrap();
+        assert_eq!(amount("2").checked_mul(quantity), None);
+        assert_eq!(
+            amount("19.90").checked_mul("2".parse().unwrap()),
+            Some(amount("39.8"))
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "decimal overflow")]
+    fn operators_panic_on_overflow() {
+        let _ = amount(&i128::MAX.to_string()) + amount("1");
+    }
+
+    #[test]
+    fn new_rounds_to_28_decimals() {
+        assert_eq!(Amount::new(1, 30), Amount::zero());
+        assert_eq!(Amount::new(5, 29), Amount::new(1, 28));
+        assert_eq!(Amount::new(-5, 29), Amount::new(-1, 28));
+        assert_eq!(Amount::new(123_456, 30), Amount::new(1235, 28));
+        assert_eq!(Amount::new(i64::MAX, 100), Amount::zero());
+        assert_eq!(Amount::new(1250, 2).to_string(), "12.5");
+    }
+
+    #[test]
+    fn deserializes_numbers_and_strings() {
+        let from = |json: &str| serde_json::from_str::<Amount>(json).unwrap();
+        assert_eq!(from("12"), amount("12"));
+        assert_eq!(from("-12"), amount("-12"));
+        assert_eq!(from("12.5"), amount("12.5"));
+        assert_eq!(from("0.1"), amount("0.1"));
+        assert_eq!(from("\"12.50\""), amount("12.5"));
+        assert_eq!(from("\"-0.1\""), amount("-0.1"));
+        assert_eq!(from("18446744073709551615"), amount("18446744073709551615"));
+
+        assert!(serde_json::from_str::<Amount>("\"abc\"").is_err());
+        assert!(serde_json::from_str::<Amount>("\"\"").is_err());
+        assert!(serde_json::from_str::<Amount>("null").is_err());
+        assert!(serde_json::from_str::<Amount>("true").is_err());
+    }
+
+    #[test]
+    fn serializes_amounts_as_numbers_and_quantities_as_strings() {
+        assert_eq!(serde_json::to_string(&amount("12")).unwrap(), "12");
+        assert_eq!(serde_json::to_string(&amount("0.1")).unwrap(), "0.1");
+        let quantity: Quantity = "1.50".parse().unwrap();
+        assert_eq!(serde_json::to_string(&quantity).unwrap(), "\"1.5\"");
+    }
+}
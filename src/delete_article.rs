@@ -0,0 +1,9 @@
+use crate::{Article, Error, Gateway};
+
+impl Gateway {
+    // Fortnox refuses to delete an article that is used on any document; set
+    // `active` to false with `edit_article` instead.
+    pub async fn delete_article(&self, article_number: &str) -> Result<(), Error> {
+        self.delete::<Article>(article_number).await
+    }
+}
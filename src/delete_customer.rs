@@ -0,0 +1,9 @@
+use crate::{Customer, Error, Gateway};
+
+impl Gateway {
+    // Fortnox refuses to delete a customer that is used on any document; set
+    // `active` to false with `edit_customer` instead.
+    pub async fn delete_customer(&self, customer_number: &str) -> Result<(), Error> {
+        self.delete::<Customer>(customer_number).await
+    }
+}
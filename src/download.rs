@@ -0,0 +1,90 @@
+use crate::{Error, Gateway};
+use bytes::Bytes;
+use futures::stream::{Stream, TryStreamExt};
+use std::fmt;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+
+// Sent as Accept when any file type will do.
+const ANY: &str = "*/*";
+
+// A file downloaded from Fortnox, e.g. a PDF or a SIE file.
+#[derive(Debug, Clone)]
+pub struct Download {
+    pub content_type: Option<String>,
+    pub bytes: Bytes,
+}
+
+impl Download {
+    pub(crate) async fn read(res: reqwest::Response) -> Result<Download, Error> {
+        let content_type = content_type(&res);
+        match res.bytes().await {
+            Ok(bytes) => Ok(Download {
+                content_type,
+                bytes,
+            }),
+            Err(err) => {
+                Err(Error::network(format!("Could not retrieve body ({}).", err)).with_source(err))
+            }
+        }
+    }
+}
+
+// A file that is handed over in chunks as it arrives, for downloads too large to
+// keep in memory, such as archive files.
+pub struct DownloadStream {
+    pub content_type: Option<String>,
+    pub content_length: Option<u64>,
+    chunks: Pin<Box<dyn Stream<Item = Result<Bytes, Error>> + Send>>,
+}
+
+impl DownloadStream {
+    pub(crate) fn new(res: reqwest::Response) -> DownloadStream {
+        DownloadStream {
+            content_type: content_type(&res),
+            content_length: res.content_length(),
+            chunks: Box::pin(res.bytes_stream().map_err(|err| {
+                Error::network(format!("Could not retrieve body ({}).", err)).with_source(err)
+            })),
+        }
+    }
+}
+
+impl Stream for DownloadStream {
+    type Item = Result<Bytes, Error>;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        self.chunks.as_mut().poll_next(cx)
+    }
+}
+
+impl fmt::Debug for DownloadStream {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("DownloadStream")
+            .field("content_type", &self.content_type)
+            .field("content_length", &self.content_length)
+            .finish()
+    }
+}
+
+impl Gateway {
+    // Downloads a file that has no method of its own, e.g. `sie/4` or
+    // `archive/{id}`. `path` is relative to the base URL.
+    pub async fn download(&self, path: &str) -> Result<Download, Error> {
+        let url = format!("{}/{}", self.base_url, path.trim_start_matches('/'));
+        self.get_bytes(&url, ANY).await
+    }
+
+    // Like `download`, but hands the file over as it arrives.
+    pub async fn download_stream(&self, path: &str) -> Result<DownloadStream, Error> {
+        let url = format!("{}/{}", self.base_url, path.trim_start_matches('/'));
+        self.get_stream(&url, ANY).await
+    }
+}
+
+fn content_type(res: &reqwest::Response) -> Option<String> {
+    res.headers()
+        .get(reqwest::header::CONTENT_TYPE)
+        .and_then(|value| value.to_str().ok())
+        .map(String::from)
+}
@@ -0,0 +1,7 @@
+use crate::{Customer, Error, Gateway};
+
+impl Gateway {
+    pub async fn edit_customer(&self, customer: &Customer) -> Result<Customer, Error> {
+        self.update(customer).await
+    }
+}
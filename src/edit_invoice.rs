@@ -0,0 +1,7 @@
+use crate::{Error, Gateway, Invoice};
+
+impl Gateway {
+    pub async fn edit_invoice(&self, invoice: &Invoice) -> Result<Invoice, Error> {
+        self.update(invoice).await
+    }
+}
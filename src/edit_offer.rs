@@ -0,0 +1,7 @@
+use crate::{Error, Gateway, Offer};
+
+impl Gateway {
+    pub async fn edit_offer(&self, offer: &Offer) -> Result<Offer, Error> {
+        self.update(offer).await
+    }
+}
@@ -0,0 +1,39 @@
+use crate::lenient;
+use serde::{Deserialize, Serialize};
+
+// The e-mail a document is sent with.
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+#[serde(rename_all = "PascalCase")]
+pub struct EmailInformation {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(default)]
+    #[serde(deserialize_with = "lenient::option_string")]
+    pub email_address_from: Option<String>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(default)]
+    #[serde(deserialize_with = "lenient::option_string")]
+    pub email_address_to: Option<String>,
+
+    #[serde(rename = "EmailAddressCC")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(default)]
+    #[serde(deserialize_with = "lenient::option_string")]
+    pub email_address_cc: Option<String>,
+
+    #[serde(rename = "EmailAddressBCC")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(default)]
+    #[serde(deserialize_with = "lenient::option_string")]
+    pub email_address_bcc: Option<String>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(default)]
+    #[serde(deserialize_with = "lenient::option_string")]
+    pub email_subject: Option<String>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(default)]
+    #[serde(deserialize_with = "lenient::option_string")]
+    pub email_body: Option<String>,
+}
@@ -0,0 +1,18 @@
+use crate::{Error, Gateway, Invoice};
+use serde::Deserialize;
+
+impl Gateway {
+    // Sends the invoice to the customer by e-mail, using the invoice's
+    // `email_information` where it is set.
+    pub async fn email_invoice(&self, document_number: &str) -> Result<Invoice, Error> {
+        #[derive(Deserialize)]
+        #[serde(rename_all = "PascalCase")]
+        struct Response {
+            invoice: Invoice,
+        }
+
+        let url = self.action_url("invoices", document_number, "email")?;
+        let res: Response = self.http_get_with_side_effects(&url).await?;
+        Ok(res.invoice)
+    }
+}
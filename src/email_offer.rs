@@ -0,0 +1,18 @@
+use crate::{Error, Gateway, Offer};
+use serde::Deserialize;
+
+impl Gateway {
+    // Sends the offer to the customer by e-mail, using the offer's
+    // `email_information` where it is set.
+    pub async fn email_offer(&self, document_number: &str) -> Result<Offer, Error> {
+        #[derive(Deserialize)]
+        #[serde(rename_all = "PascalCase")]
+        struct Response {
+            offer: Offer,
+        }
+
+        let url = self.action_url("offers", document_number, "email")?;
+        let res: Response = self.http_get_with_side_effects(&url).await?;
+        Ok(res.offer)
+    }
+}
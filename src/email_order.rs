@@ -0,0 +1,18 @@
+use crate::{Error, Gateway, Order};
+use serde::Deserialize;
+
+impl Gateway {
+    // Sends the order confirmation to the customer by e-mail, using the order's
+    // `email_information` where it is set.
+    pub async fn email_order(&self, document_number: &str) -> Result<Order, Error> {
+        #[derive(Deserialize)]
+        #[serde(rename_all = "PascalCase")]
+        struct Response {
+            order: Order,
+        }
+
+        let url = self.action_url("orders", document_number, "email")?;
+        let res: Response = self.http_get_with_side_effects(&url).await?;
+        Ok(res.order)
+    }
+}
@@ -1,27 +1,369 @@
-use crate::api_error_code::ApiErrorCode;
+use crate::api_error_code::{ApiErrorCategory, ApiErrorCode};
 use serde::{Deserialize, Serialize};
+use std::error::Error as StdError;
 use std::fmt;
+use std::sync::Arc;
 
-#[derive(Serialize, Deserialize, Debug, Clone)]
-pub enum Error {
-    Unspecified(String),
-    ParseError(String),
-    SerializationError(String),
-    NetworkError(String),
-    ApiError(ApiErrorCode, String),
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, Eq, PartialEq)]
+pub enum ErrorKind {
+    Unspecified,
+    Parse,
+    Serialization,
+    Network,
+    // Fortnox refused the request, see `Error::code`.
+    Api,
     Throttling,
+    Unauthorized,
+    // Fortnox, or something in front of it, failed without saying why.
+    Server,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct Error {
+    kind: ErrorKind,
+    message: String,
+
+    // The `Code` of Fortnox's error information.
+    code: Option<u32>,
+
+    context: Option<Box<Context>>,
+
+    // Whether a network error happened while connecting, before any of the
+    // request was sent.
+    #[serde(default)]
+    connect: bool,
+
+    // E.g. the reqwest or serde error this was made from.
+    #[serde(skip)]
+    source: Option<Arc<dyn StdError + Send + Sync>>,
+}
+
+// The request that failed and what Fortnox answered.
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+struct Context {
+    method: Option<String>,
+    path: Option<String>,
+    status: Option<u16>,
+    body: Option<String>,
+}
+
+impl Error {
+    pub(crate) fn new(kind: ErrorKind, message: String) -> Error {
+        Error {
+            kind,
+            message,
+            code: None,
+            context: None,
+            connect: false,
+            source: None,
+        }
+    }
+
+    pub(crate) fn unspecified(message: String) -> Error {
+        Error::new(ErrorKind::Unspecified, message)
+    }
+
+    pub(crate) fn parse(message: String) -> Error {
+        Error::new(ErrorKind::Parse, message)
+    }
+
+    pub(crate) fn serialization(message: String) -> Error {
+        Error::new(ErrorKind::Serialization, message)
+    }
+
+    pub(crate) fn network(message: String) -> Error {
+        Error::new(ErrorKind::Network, message)
+    }
+
+    pub(crate) fn unauthorized(message: String) -> Error {
+        Error::new(ErrorKind::Unauthorized, message)
+    }
+
+    pub(crate) fn throttling() -> Error {
+        Error::new(ErrorKind::Throttling, "Throttling.".to_string())
+    }
+
+    pub(crate) fn server(message: String) -> Error {
+        Error::new(ErrorKind::Server, message)
+    }
+
+    pub(crate) fn api(code: u32, message: String) -> Error {
+        Error::new(ErrorKind::Api, message).with_code(code)
+    }
+
+    pub(crate) fn with_source(mut self, source: impl StdError + Send + Sync + 'static) -> Error {
+        self.source = Some(Arc::new(source));
+        self
+    }
+
+    pub(crate) fn with_code(mut self, code: u32) -> Error {
+        self.code = Some(code);
+        self
+    }
+
+    pub(crate) fn with_connect(mut self, connect: bool) -> Error {
+        self.connect = connect;
+        self
+    }
+
+    pub(crate) fn with_request(mut self, method: &reqwest::Method, url: &str) -> Error {
+        let path = match reqwest::Url::parse(url) {
+            Ok(url) => url.path().to_string(),
+            Err(_) => url.to_string(),
+        };
+        let context = self.context.get_or_insert_with(Box::default);
+        context.method = Some(method.to_string());
+        context.path = Some(path);
+        self
+    }
+
+    pub(crate) fn with_response(mut self, status: Option<u16>, body: String) -> Error {
+        let context = self.context.get_or_insert_with(Box::default);
+        context.status = status;
+        context.body = Some(body);
+        self
+    }
+
+    pub fn kind(&self) -> ErrorKind {
+        self.kind
+    }
+
+    pub fn message(&self) -> &str {
+        &self.message
+    }
+
+    // The raw error code from Fortnox, for API errors.
+    pub fn code(&self) -> Option<u32> {
+        self.code
+    }
+
+    // The error code from Fortnox, as `ApiErrorCode::Other` if it is not listed.
+    pub fn api_error_code(&self) -> Option<ApiErrorCode> {
+        self.code.map(ApiErrorCode::from_code)
+    }
+
+    // What the error code means in English, as the message from Fortnox is
+    // often in Swedish.
+    pub fn description(&self) -> Option<&'static str> {
+        self.api_error_code()?.description()
+    }
+
+    // The field that the message from Fortnox names, e.g. "Name" in
+    // "Felaktigt fältnamn (Name)".
+    pub fn field(&self) -> Option<String> {
+        if self.kind != ErrorKind::Api {
+            return None;
+        }
+        if self.api_error_code() == Some(ApiErrorCode::InvalidFieldName) {
+            if let Some(field) =
+                trailing_value(&self.message).or_else(|| after_colon(&self.message))
+            {
+                return Some(field);
+            }
+        }
+        field_name(&self.message)
+    }
+
+    // The value that the message from Fortnox quotes, e.g. "abc" in "Värdet
+    // måste vara numeriskt (abc)".
+    pub fn value(&self) -> Option<String> {
+        if self.kind != ErrorKind::Api {
+            return None;
+        }
+        // The parentheses name the field here, not a value.
+        if self.api_error_code() == Some(ApiErrorCode::InvalidFieldName) {
+            return None;
+        }
+        trailing_value(&self.message)
+    }
+
+    // The HTTP method of the request that failed, e.g. "GET".
+    pub fn method(&self) -> Option<&str> {
+        self.context.as_ref()?.method.as_deref()
+    }
+
+    // The path of the request that failed, e.g. "/3/customers/1".
+    pub fn path(&self) -> Option<&str> {
+        self.context.as_ref()?.path.as_deref()
+    }
+
+    // The HTTP status of the response, if there was one.
+    pub fn status(&self) -> Option<u16> {
+        self.context.as_ref()?.status
+    }
+
+    // The response body as received, if there was one.
+    pub fn body(&self) -> Option<&str> {
+        self.context.as_ref()?.body.as_deref()
+    }
+
+    // The connection to Fortnox could not be made, so the request never reached
+    // it.
+    pub fn is_connect(&self) -> bool {
+        self.connect
+    }
+
+    pub fn is_not_found(&self) -> bool {
+        if self.status() == Some(404) {
+            return true;
+        }
+        match self.api_error_code() {
+            Some(code) => code.is_not_found(),
+            None => false,
+        }
+    }
+
+    // The credentials were refused, or do not give access to what was asked for.
+    pub fn is_auth(&self) -> bool {
+        if self.kind == ErrorKind::Unauthorized || matches!(self.status(), Some(401) | Some(403)) {
+            return true;
+        }
+        match self.api_error_code() {
+            Some(code) => code.category() == ApiErrorCategory::Auth,
+            None => false,
+        }
+    }
+
+    // Throttling, server errors and failed connections, which may well succeed
+    // if the request is sent again.
+    pub fn is_retryable(&self) -> bool {
+        match self.kind {
+            ErrorKind::Throttling | ErrorKind::Network | ErrorKind::Server => true,
+            ErrorKind::Api => match self.api_error_code() {
+                Some(code) => code.category() == ApiErrorCategory::System,
+                None => false,
+            },
+            _ => false,
+        }
+    }
 }
 
 impl fmt::Display for Error {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        let text = match self {
-            Error::Unspecified(g) => g,
-            Error::ParseError(g) => g,
-            Error::SerializationError(g) => g,
-            Error::NetworkError(g) => g,
-            Error::ApiError(_, g) => g,
-            Error::Throttling => "Throttling.",
-        };
-        write!(f, "{}", text)
+        write!(f, "{}", self.message)
+    }
+}
+
+impl StdError for Error {
+    fn source(&self) -> Option<&(dyn StdError + 'static)> {
+        match &self.source {
+            Some(source) => Some(source.as_ref()),
+            None => None,
+        }
+    }
+}
+
+// The text in parentheses at the end of a message, which is where Fortnox puts
+// the value it refused.
+fn trailing_value(message: &str) -> Option<String> {
+    let message = message.trim_end().trim_end_matches('.');
+    let inner = message.strip_suffix(')')?;
+    let start = inner.rfind(" (")?;
+    let value = inner[start + 2..].trim();
+    if value.is_empty() {
+        return None;
+    }
+    Some(value.to_string())
+}
+
+fn after_colon(message: &str) -> Option<String> {
+    let (_, rest) = message.split_once(':')?;
+    identifier(rest.split_whitespace().next()?)
+}
+
+// A field is named either after a word like "fält" or "field", or first in the
+// message followed by a colon, as in "OrganisationNumber: ...".
+fn field_name(message: &str) -> Option<String> {
+    let words: Vec<&str> = message.split_whitespace().collect();
+    for pair in words.windows(2) {
+        let keyword = pair[0].trim_end_matches(':').to_lowercase();
+        if matches!(
+            keyword.as_str(),
+            "fält" | "fältet" | "fältnamn" | "field" | "fieldname"
+        ) {
+            if let Some(field) = identifier(pair[1]) {
+                return Some(field);
+            }
+        }
+    }
+
+    let first = words.first()?;
+    let first = first.strip_suffix(':')?;
+    if first.starts_with(|c: char| c.is_ascii_uppercase()) {
+        return identifier(first);
+    }
+    None
+}
+
+// The word without quotes and punctuation, if it looks like a field name.
+fn identifier(word: &str) -> Option<String> {
+    let word = word.trim_matches(|c: char| !(c.is_ascii_alphanumeric() || c == '_'));
+    if word.is_empty() || !word.chars().all(|c| c.is_ascii_alphanumeric() || c == '_') {
+        return None;
+    }
+    Some(word.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn api(code: u32, message: &str) -> Error {
+        Error::api(code, message.to_string())
+    }
+
+    #[test]
+    fn invalid_field_name_names_the_field() {
+        let err = api(2001399, "Felaktigt fältnamn (Name)");
+        assert_eq!(err.field().as_deref(), Some("Name"));
+        assert_eq!(err.value(), None);
+
+        let err = api(2001399, "Felaktigt fältnamn (EmailAddressCC).");
+        assert_eq!(err.field().as_deref(), Some("EmailAddressCC"));
+        assert_eq!(err.value(), None);
+
+        let err = api(2001399, "Invalid field name: Foo");
+        assert_eq!(err.field().as_deref(), Some("Foo"));
+        assert_eq!(err.value(), None);
+    }
+
+    #[test]
+    fn quoted_values() {
+        let err = api(2000108, "Värdet måste vara numeriskt (abc)");
+        assert_eq!(err.value().as_deref(), Some("abc"));
+        assert_eq!(err.field(), None);
+
+        let err = api(2000106, "Värdet måste vara alfanumeriskt (10 20)");
+        assert_eq!(err.value().as_deref(), Some("10 20"));
+
+        let err = api(2000433, "Kunde inte hitta kund (1234).");
+        assert_eq!(err.value().as_deref(), Some("1234"));
+
+        let err = api(2000108, "Värdet måste vara numeriskt ()");
+        assert_eq!(err.value(), None);
+    }
+
+    #[test]
+    fn named_fields() {
+        let err = api(2000359, "Ogiltiga tecken i fältet Address1");
+        assert_eq!(err.field().as_deref(), Some("Address1"));
+        assert_eq!(err.value(), None);
+
+        let err = api(2000108, "Field 'OrganisationNumber' must be numeric");
+        assert_eq!(err.field().as_deref(), Some("OrganisationNumber"));
+
+        let err = api(2000588, "OrganisationNumber: Ogiltigt organisationsnummer");
+        assert_eq!(err.field().as_deref(), Some("OrganisationNumber"));
+
+        let err = api(2000204, "Kunde inte hitta kunden");
+        assert_eq!(err.field(), None);
+        assert_eq!(err.value(), None);
+    }
+
+    #[test]
+    fn only_api_errors_have_fields_and_values() {
+        let err = Error::parse("Felaktigt fältnamn (Name)".to_string());
+        assert_eq!(err.field(), None);
+        assert_eq!(err.value(), None);
     }
 }
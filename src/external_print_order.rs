@@ -0,0 +1,24 @@
+use crate::{Error, Gateway, Order};
+use serde::{Deserialize, Serialize};
+
+impl Gateway {
+    // Marks the order as sent without Fortnox sending it, e.g. when it has been
+    // printed or sent by other means.
+    pub async fn external_print_order(&self, document_number: &str) -> Result<Order, Error> {
+        #[derive(Serialize)]
+        #[serde(rename_all = "PascalCase")]
+        struct Request {}
+
+        let body = Request {};
+
+        #[derive(Deserialize)]
+        #[serde(rename_all = "PascalCase")]
+        struct Response {
+            order: Order,
+        }
+
+        let url = self.action_url("orders", document_number, "externalprint")?;
+        let res: Response = self.http_put(&url, &body).await?;
+        Ok(res.order)
+    }
+}
@@ -0,0 +1,202 @@
+use crate::oauth::{self, Persistence, Session};
+use crate::{
+    Error, Gateway, OAuthClient, RateLimiter, RetryPolicy, TokenSet, TokenStore, BASE_URL,
+};
+use std::sync::Arc;
+use std::time::Duration;
+
+const TIMEOUT_SECS: u64 = 60;
+
+enum Credentials {
+    AccessToken {
+        token: String,
+        secret: String,
+    },
+    OAuth {
+        client: OAuthClient,
+        tokens: TokenSet,
+        persistence: Persistence,
+    },
+    TokenStore {
+        client: OAuthClient,
+        store: Arc<dyn TokenStore>,
+    },
+}
+
+pub struct GatewayBuilder {
+    credentials: Option<Credentials>,
+    base_url: String,
+    https_only: bool,
+    timeout: Duration,
+    user_agent: Option<String>,
+    retry_policy: RetryPolicy,
+    rate_limiter: Option<RateLimiter>,
+}
+
+impl GatewayBuilder {
+    pub fn new() -> GatewayBuilder {
+        GatewayBuilder {
+            credentials: None,
+            base_url: BASE_URL.to_string(),
+            https_only: true,
+            timeout: Duration::from_secs(TIMEOUT_SECS),
+            user_agent: None,
+            retry_policy: RetryPolicy::default(),
+            rate_limiter: Some(RateLimiter::default()),
+        }
+    }
+
+    // Authenticates with the legacy Access-Token and Client-Secret headers.
+    pub fn access_token(mut self, token: String, secret: String) -> GatewayBuilder {
+        self.credentials = Some(Credentials::AccessToken { token, secret });
+        self
+    }
+
+    // Authenticates with OAuth2. The refresh token is exchanged for an access
+    // token when the gateway is built, and every time Fortnox rotates the refresh
+    // token the new token set is passed to `on_refresh`, which should persist it
+    // (the previous refresh token stops working).
+    pub fn oauth(
+        mut self,
+        client: OAuthClient,
+        refresh_token: String,
+        on_refresh: impl Fn(&TokenSet) + Send + Sync + 'static,
+    ) -> GatewayBuilder {
+        self.credentials = Some(Credentials::OAuth {
+            client,
+            tokens: TokenSet::from_refresh_token(refresh_token),
+            persistence: Persistence::Callback(Box::new(on_refresh)),
+        });
+        self
+    }
+
+    // Authenticates with OAuth2 using the tokens in `store`, which must already
+    // hold at least a refresh token (see `TokenSet::from_refresh_token`). Rotated
+    // tokens are written back to the store, so several processes can share one
+    // Fortnox tenant.
+    pub fn token_store(
+        mut self,
+        client: OAuthClient,
+        store: Arc<dyn TokenStore>,
+    ) -> GatewayBuilder {
+        self.credentials = Some(Credentials::TokenStore { client, store });
+        self
+    }
+
+    // The URL the resource paths are appended to, e.g. to run against a local
+    // mock server. Defaults to Fortnox's production API.
+    pub fn base_url(mut self, base_url: impl Into<String>) -> GatewayBuilder {
+        self.base_url = base_url.into().trim_end_matches('/').to_string();
+        self
+    }
+
+    // Whether plain http requests to the API are refused. Defaults to true. The
+    // OAuth token endpoint follows `OAuthClient::https_only` instead.
+    pub fn https_only(mut self, https_only: bool) -> GatewayBuilder {
+        self.https_only = https_only;
+        self
+    }
+
+    pub fn timeout(mut self, timeout: Duration) -> GatewayBuilder {
+        self.timeout = timeout;
+        self
+    }
+
+    pub fn user_agent(mut self, user_agent: impl Into<String>) -> GatewayBuilder {
+        self.user_agent = Some(user_agent.into());
+        self
+    }
+
+    pub fn retry_policy(mut self, retry_policy: RetryPolicy) -> GatewayBuilder {
+        self.retry_policy = retry_policy;
+        self
+    }
+
+    // Throttles requests before they are sent, so that bulk jobs stay within
+    // the tenant's quota instead of running into 429 responses. Defaults to
+    // Fortnox's limit of 25 requests per 5 seconds. Pass a clone of the same
+    // limiter to every gateway that uses the tenant, or `None` to turn it off.
+    pub fn rate_limiter(mut self, rate_limiter: Option<RateLimiter>) -> GatewayBuilder {
+        self.rate_limiter = rate_limiter;
+        self
+    }
+
+    pub async fn build(self) -> Result<Gateway, Error> {
+        let mut headers = Gateway::default_headers();
+        let user_agent = self.user_agent.as_deref();
+
+        let (client, tokens, persistence) = match self.credentials {
+            None => {
+                return Err(Error::unspecified(
+                    "No credentials were given to the gateway builder.".to_string(),
+                ))
+            }
+            Some(Credentials::AccessToken { token, secret }) => {
+                headers.insert("Access-Token", header_value(&token)?);
+                headers.insert("Client-Secret", header_value(&secret)?);
+
+                let client =
+                    Gateway::build_client(headers, self.https_only, self.timeout, user_agent)?;
+                return Ok(Gateway {
+                    client,
+                    base_url: self.base_url,
+                    retry_policy: self.retry_policy,
+                    rate_limiter: self.rate_limiter,
+                    oauth: None,
+                });
+            }
+            Some(Credentials::OAuth {
+                client,
+                tokens,
+                persistence,
+            }) => (client, tokens, persistence),
+            Some(Credentials::TokenStore { client, store }) => {
+                let tokens = match oauth::blocking(&store, |store| store.load()).await? {
+                    Some(tokens) => tokens,
+                    None => {
+                        return Err(Error::unauthorized(
+                            "The token store holds no tokens.".to_string(),
+                        ))
+                    }
+                };
+                (client, tokens, Persistence::Store(store))
+            }
+        };
+
+        let token_client = Gateway::build_client(
+            reqwest::header::HeaderMap::new(),
+            client.https_only,
+            self.timeout,
+            user_agent,
+        )?;
+
+        // Exchange the refresh token right away if needed, so that bad
+        // credentials are reported here rather than on the first request.
+        let session = Session::new(client, token_client, tokens, persistence);
+        session.access_token().await?;
+
+        let client = Gateway::build_client(headers, self.https_only, self.timeout, user_agent)?;
+        Ok(Gateway {
+            client,
+            base_url: self.base_url,
+            retry_policy: self.retry_policy,
+            rate_limiter: self.rate_limiter,
+            oauth: Some(Arc::new(session)),
+        })
+    }
+}
+
+impl Default for GatewayBuilder {
+    fn default() -> GatewayBuilder {
+        GatewayBuilder::new()
+    }
+}
+
+fn header_value(value: &str) -> Result<reqwest::header::HeaderValue, Error> {
+    match reqwest::header::HeaderValue::from_str(value) {
+        Ok(header) => Ok(header),
+        Err(err) => Err(
+            Error::unspecified(format!("Could not create auth header ({}).", err)).with_source(err),
+        ),
+    }
+}
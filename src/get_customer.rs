@@ -1,16 +1,7 @@
-use crate::{Customer, Error, Gateway, BASE_URL};
-use serde::Deserialize;
+use crate::{Customer, Error, Gateway};
 
 impl Gateway {
-    pub async fn get_customer<'a>(&self, customer_number: &'a str) -> Result<Customer, Error> {
-        #[derive(Deserialize)]
-        #[serde(rename_all = "PascalCase")]
-        struct Response {
-            customer: Customer,
-        }
-
-        let url = format!("{}/customers/{}", BASE_URL, &customer_number);
-        let res: Response = self.get(&url).await?;
-        Ok(res.customer)
+    pub async fn get_customer(&self, customer_number: &str) -> Result<Customer, Error> {
+        self.get(customer_number).await
     }
 }
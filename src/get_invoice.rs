@@ -0,0 +1,7 @@
+use crate::{Error, Gateway, Invoice};
+
+impl Gateway {
+    pub async fn get_invoice(&self, document_number: &str) -> Result<Invoice, Error> {
+        self.get(document_number).await
+    }
+}
@@ -0,0 +1,7 @@
+use crate::{Error, Gateway, Offer};
+
+impl Gateway {
+    pub async fn get_offer(&self, document_number: &str) -> Result<Offer, Error> {
+        self.get(document_number).await
+    }
+}
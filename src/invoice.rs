@@ -0,0 +1,452 @@
+use crate::order::serialize_unknown;
+use crate::validation::Validator;
+use crate::{
+    lenient, Amount, Currency, EmailInformation, FieldError, Label, Language, Resource, Row,
+    ValidationMode,
+};
+use chrono::NaiveDate;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "PascalCase")]
+pub struct Invoice {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(default)]
+    #[serde(deserialize_with = "lenient::option_string")]
+    pub document_number: Option<String>,
+
+    #[serde(deserialize_with = "lenient::string")]
+    pub customer_number: String,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(default)]
+    #[serde(deserialize_with = "lenient::option_string")]
+    pub customer_name: Option<String>,
+
+    #[serde(default)]
+    pub invoice_rows: Vec<Row>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(default)]
+    #[serde(deserialize_with = "lenient::option")]
+    pub currency: Option<Currency>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(rename = "VATIncluded")]
+    #[serde(default)]
+    #[serde(deserialize_with = "lenient::option_boolean")]
+    pub vat_included: Option<bool>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(default)]
+    #[serde(deserialize_with = "lenient::option")]
+    pub invoice_date: Option<NaiveDate>, // "2006-01-02"
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(default)]
+    #[serde(deserialize_with = "lenient::option")]
+    pub due_date: Option<NaiveDate>, // "2006-01-02"
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(default)]
+    #[serde(deserialize_with = "lenient::option")]
+    pub delivery_date: Option<NaiveDate>, // "2006-01-02"
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(default)]
+    #[serde(deserialize_with = "lenient::option_string")]
+    pub invoice_type: Option<String>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(rename = "OCR")]
+    #[serde(default)]
+    #[serde(deserialize_with = "lenient::option_string")]
+    pub ocr: Option<String>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(default)]
+    #[serde(deserialize_with = "lenient::option_string")]
+    pub comments: Option<String>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(default)]
+    #[serde(deserialize_with = "lenient::option_string")]
+    pub remarks: Option<String>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(default)]
+    #[serde(deserialize_with = "lenient::option_string")]
+    pub address1: Option<String>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(default)]
+    #[serde(deserialize_with = "lenient::option_string")]
+    pub address2: Option<String>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(default)]
+    #[serde(deserialize_with = "lenient::option_string")]
+    pub zip_code: Option<String>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(default)]
+    #[serde(deserialize_with = "lenient::option_string")]
+    pub city: Option<String>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(default)]
+    #[serde(deserialize_with = "lenient::option_string")]
+    pub country: Option<String>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(default)]
+    #[serde(deserialize_with = "lenient::option_string")]
+    pub delivery_name: Option<String>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(default)]
+    #[serde(deserialize_with = "lenient::option_string")]
+    pub delivery_address1: Option<String>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(default)]
+    #[serde(deserialize_with = "lenient::option_string")]
+    pub delivery_address2: Option<String>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(default)]
+    #[serde(deserialize_with = "lenient::option_string")]
+    pub delivery_zip_code: Option<String>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(default)]
+    #[serde(deserialize_with = "lenient::option_string")]
+    pub delivery_city: Option<String>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(default)]
+    #[serde(deserialize_with = "lenient::option_string")]
+    pub delivery_country: Option<String>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(default)]
+    #[serde(deserialize_with = "lenient::option_string")]
+    pub our_reference: Option<String>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(default)]
+    #[serde(deserialize_with = "lenient::option_string")]
+    pub your_reference: Option<String>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(default)]
+    #[serde(deserialize_with = "lenient::option_string")]
+    pub your_order_number: Option<String>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(default)]
+    #[serde(deserialize_with = "lenient::option_string")]
+    pub project: Option<String>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(default)]
+    #[serde(deserialize_with = "lenient::option_string")]
+    pub cost_center: Option<String>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(default)]
+    #[serde(deserialize_with = "lenient::option_string")]
+    pub price_list: Option<String>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(default)]
+    #[serde(deserialize_with = "lenient::option_string")]
+    pub terms_of_payment: Option<String>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(default)]
+    #[serde(deserialize_with = "lenient::option_string")]
+    pub terms_of_delivery: Option<String>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(default)]
+    #[serde(deserialize_with = "lenient::option_string")]
+    pub way_of_delivery: Option<String>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(default)]
+    #[serde(deserialize_with = "lenient::option")]
+    pub freight: Option<Amount>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(default)]
+    #[serde(deserialize_with = "lenient::option")]
+    pub administration_fee: Option<Amount>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(default)]
+    #[serde(deserialize_with = "lenient::option_string")]
+    pub external_invoice_reference1: Option<String>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(default)]
+    #[serde(deserialize_with = "lenient::option_string")]
+    pub external_invoice_reference2: Option<String>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(default)]
+    #[serde(deserialize_with = "lenient::option")]
+    pub language: Option<Language>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(default)]
+    pub email_information: Option<EmailInformation>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(default)]
+    pub labels: Option<Vec<Label>>,
+
+    #[serde(skip_serializing)]
+    #[serde(default)]
+    #[serde(deserialize_with = "lenient::option_string")]
+    pub order_reference: Option<String>, // Read-only.
+
+    #[serde(skip_serializing)]
+    #[serde(default)]
+    #[serde(deserialize_with = "lenient::option_string")]
+    pub credit_invoice_reference: Option<String>, // Read-only.
+
+    #[serde(skip_serializing)]
+    #[serde(default)]
+    #[serde(deserialize_with = "lenient::boolean")]
+    pub booked: bool, // Read-only.
+
+    #[serde(skip_serializing)]
+    #[serde(default)]
+    #[serde(deserialize_with = "lenient::boolean")]
+    pub cancelled: bool, // Read-only.
+
+    #[serde(skip_serializing)]
+    #[serde(default)]
+    #[serde(deserialize_with = "lenient::boolean")]
+    pub credit: bool, // Read-only.
+
+    #[serde(skip_serializing)]
+    #[serde(default)]
+    #[serde(deserialize_with = "lenient::boolean")]
+    pub sent: bool, // Read-only.
+
+    #[serde(skip_serializing)]
+    #[serde(default)]
+    #[serde(deserialize_with = "lenient::boolean")]
+    pub warehouse_ready: bool, // Read-only.
+
+    #[serde(skip_serializing)]
+    #[serde(default)]
+    #[serde(deserialize_with = "lenient::option")]
+    pub final_pay_date: Option<NaiveDate>, // Read-only.
+
+    #[serde(skip_serializing)]
+    #[serde(default)]
+    #[serde(deserialize_with = "lenient::option")]
+    pub balance: Option<Amount>, // Read-only.
+
+    #[serde(skip_serializing)]
+    #[serde(default)]
+    #[serde(deserialize_with = "lenient::option")]
+    pub total: Option<Amount>, // Read-only.
+
+    #[serde(skip_serializing)]
+    #[serde(rename = "TotalVAT")]
+    #[serde(default)]
+    #[serde(deserialize_with = "lenient::option")]
+    pub total_vat: Option<Amount>, // Read-only.
+
+    #[serde(skip_serializing)]
+    #[serde(default)]
+    #[serde(deserialize_with = "lenient::option")]
+    pub net: Option<Amount>, // Read-only.
+
+    // Fields that have no typed counterpart above, kept so that an invoice that
+    // is fetched, changed and sent back with `edit_invoice` does not lose them.
+    #[serde(flatten)]
+    #[serde(serialize_with = "serialize_unknown")]
+    pub unknown: HashMap<String, serde_json::Value>,
+}
+
+impl Invoice {
+    // Fortnox fills in the currency, dates, terms and addresses from the
+    // customer for anything that is left as None.
+    pub fn new(customer_number: String, invoice_rows: Vec<Row>) -> Invoice {
+        Invoice {
+            document_number: None,
+            customer_number,
+            customer_name: None,
+            invoice_rows,
+            currency: None,
+            vat_included: None,
+            invoice_date: None,
+            due_date: None,
+            delivery_date: None,
+            invoice_type: None,
+            ocr: None,
+            comments: None,
+            remarks: None,
+            address1: None,
+            address2: None,
+            zip_code: None,
+            city: None,
+            country: None,
+            delivery_name: None,
+            delivery_address1: None,
+            delivery_address2: None,
+            delivery_zip_code: None,
+            delivery_city: None,
+            delivery_country: None,
+            our_reference: None,
+            your_reference: None,
+            your_order_number: None,
+            project: None,
+            cost_center: None,
+            price_list: None,
+            terms_of_payment: None,
+            terms_of_delivery: None,
+            way_of_delivery: None,
+            freight: None,
+            administration_fee: None,
+            external_invoice_reference1: None,
+            external_invoice_reference2: None,
+            language: None,
+            email_information: None,
+            labels: None,
+            order_reference: None,
+            credit_invoice_reference: None,
+            booked: false,
+            cancelled: false,
+            credit: false,
+            sent: false,
+            warehouse_ready: false,
+            final_pay_date: None,
+            balance: None,
+            total: None,
+            total_vat: None,
+            net: None,
+            unknown: HashMap::new(),
+        }
+    }
+
+    // An invoice needs a customer; an update also needs its document number.
+    pub fn validate(&self, mode: ValidationMode) -> Result<(), Vec<FieldError>> {
+        let mut v = Validator::new(mode);
+        if v.mode() == ValidationMode::Update {
+            v.required("DocumentNumber", self.document_number.as_deref());
+        }
+        v.numeric("DocumentNumber", self.document_number.as_deref(), 20);
+        v.required("CustomerNumber", Some(&self.customer_number));
+        v.identifier("CustomerNumber", Some(&self.customer_number), 1024);
+        v.text("CustomerName", self.customer_name.as_deref(), 1024);
+        v.numeric("OCR", self.ocr.as_deref(), 25);
+        v.text("Comments", self.comments.as_deref(), 1024);
+        v.text("Remarks", self.remarks.as_deref(), 1024);
+        v.text("Address1", self.address1.as_deref(), 1024);
+        v.text("Address2", self.address2.as_deref(), 1024);
+        v.text("ZipCode", self.zip_code.as_deref(), 1024);
+        v.text("City", self.city.as_deref(), 1024);
+        v.text("Country", self.country.as_deref(), 1024);
+        v.text("DeliveryName", self.delivery_name.as_deref(), 1024);
+        v.text("DeliveryAddress1", self.delivery_address1.as_deref(), 1024);
+        v.text("DeliveryAddress2", self.delivery_address2.as_deref(), 1024);
+        v.text("DeliveryZipCode", self.delivery_zip_code.as_deref(), 1024);
+        v.text("DeliveryCity", self.delivery_city.as_deref(), 1024);
+        v.text("DeliveryCountry", self.delivery_country.as_deref(), 1024);
+        v.text("OurReference", self.our_reference.as_deref(), 50);
+        v.text("YourReference", self.your_reference.as_deref(), 50);
+        v.text("YourOrderNumber", self.your_order_number.as_deref(), 30);
+        v.text(
+            "ExternalInvoiceReference1",
+            self.external_invoice_reference1.as_deref(),
+            80,
+        );
+        v.text(
+            "ExternalInvoiceReference2",
+            self.external_invoice_reference2.as_deref(),
+            80,
+        );
+        v.email_information(self.email_information.as_ref());
+        v.rows("InvoiceRows", &self.invoice_rows);
+        v.finish()
+    }
+}
+
+impl Resource for Invoice {
+    const PATH: &'static str = "invoices";
+    const KEY: &'static str = "Invoice";
+    const LIST_KEY: &'static str = "Invoices";
+    const ID_FIELD: &'static str = "DocumentNumber";
+    type ListItem = ListItem;
+
+    fn id(&self) -> Option<&str> {
+        self.document_number.as_deref()
+    }
+}
+
+// An invoice as it appears in a list, which only carries the most common fields.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "PascalCase")]
+pub struct ListItem {
+    #[serde(deserialize_with = "lenient::string")]
+    pub document_number: String,
+
+    #[serde(deserialize_with = "lenient::string")]
+    pub customer_number: String,
+
+    #[serde(default)]
+    #[serde(deserialize_with = "lenient::option_string")]
+    pub customer_name: Option<String>,
+
+    #[serde(default)]
+    #[serde(deserialize_with = "lenient::option")]
+    pub currency: Option<Currency>,
+
+    #[serde(default)]
+    #[serde(deserialize_with = "lenient::option")]
+    pub invoice_date: Option<NaiveDate>,
+
+    #[serde(default)]
+    #[serde(deserialize_with = "lenient::option")]
+    pub due_date: Option<NaiveDate>,
+
+    #[serde(default)]
+    #[serde(deserialize_with = "lenient::option")]
+    pub final_pay_date: Option<NaiveDate>,
+
+    #[serde(rename = "OCR")]
+    #[serde(default)]
+    #[serde(deserialize_with = "lenient::option_string")]
+    pub ocr: Option<String>,
+
+    #[serde(default)]
+    #[serde(deserialize_with = "lenient::option")]
+    pub total: Option<Amount>,
+
+    #[serde(default)]
+    #[serde(deserialize_with = "lenient::option")]
+    pub balance: Option<Amount>,
+
+    #[serde(default)]
+    #[serde(deserialize_with = "lenient::boolean")]
+    pub booked: bool,
+
+    #[serde(default)]
+    #[serde(deserialize_with = "lenient::boolean")]
+    pub cancelled: bool,
+
+    #[serde(default)]
+    #[serde(deserialize_with = "lenient::boolean")]
+    pub sent: bool,
+
+    #[serde(default)]
+    #[serde(deserialize_with = "lenient::option_string")]
+    pub your_reference: Option<String>,
+}
@@ -0,0 +1,184 @@
+// Deserializers for the fields that Fortnox does not encode consistently, for
+// use with `#[serde(deserialize_with = "...")]`. Depending on the endpoint the
+// same field can come back as a number or a string, an unset field as an empty
+// string and a flag as "0"/"1".
+use serde::de::{DeserializeOwned, Deserializer, Error};
+use serde::Deserialize;
+use serde_json::Value;
+
+// A string that may be sent as a number, e.g. a document number.
+pub(crate) fn string<'de, D: Deserializer<'de>>(deserializer: D) -> Result<String, D::Error> {
+    match Value::deserialize(deserializer)? {
+        Value::String(s) => Ok(s),
+        Value::Number(n) => Ok(n.to_string()),
+        other => Err(D::Error::custom(format!(
+            "expected a string or a number, found {}",
+            other
+        ))),
+    }
+}
+
+// Like `string`, but null and blank strings are None.
+pub(crate) fn option_string<'de, D: Deserializer<'de>>(
+    deserializer: D,
+) -> Result<Option<String>, D::Error> {
+    match Value::deserialize(deserializer)? {
+        Value::Null => Ok(None),
+        Value::String(s) if s.trim().is_empty() => Ok(None),
+        Value::String(s) => Ok(Some(s)),
+        Value::Number(n) => Ok(Some(n.to_string())),
+        other => Err(D::Error::custom(format!(
+            "expected a string or a number, found {}",
+            other
+        ))),
+    }
+}
+
+// Any other optional value, where null and blank strings are None and a number
+// may be sent as a string.
+pub(crate) fn option<'de, D: Deserializer<'de>, T: DeserializeOwned>(
+    deserializer: D,
+) -> Result<Option<T>, D::Error> {
+    match Value::deserialize(deserializer)? {
+        Value::Null => Ok(None),
+        Value::String(s) if s.trim().is_empty() => Ok(None),
+        Value::String(s) => match T::deserialize(Value::String(s.clone())) {
+            Ok(value) => Ok(Some(value)),
+            Err(err) => match s.trim().parse::<serde_json::Number>() {
+                Ok(n) => T::deserialize(Value::Number(n))
+                    .map(Some)
+                    .map_err(D::Error::custom),
+                Err(_) => Err(D::Error::custom(err)),
+            },
+        },
+        value => T::deserialize(value).map(Some).map_err(D::Error::custom),
+    }
+}
+
+// A flag that may be sent as a boolean, as 0/1 or as "0"/"1".
+pub(crate) fn boolean<'de, D: Deserializer<'de>>(deserializer: D) -> Result<bool, D::Error> {
+    let value = Value::deserialize(deserializer)?;
+    match flag(&value) {
+        Some(flag) => Ok(flag),
+        None => Err(D::Error::custom(format!(
+            "expected a boolean, found {}",
+            value
+        ))),
+    }
+}
+
+// Like `boolean`, but null and blank strings are None.
+pub(crate) fn option_boolean<'de, D: Deserializer<'de>>(
+    deserializer: D,
+) -> Result<Option<bool>, D::Error> {
+    let value = Value::deserialize(deserializer)?;
+    match &value {
+        Value::Null => return Ok(None),
+        Value::String(s) if s.trim().is_empty() => return Ok(None),
+        _ => {}
+    }
+    match flag(&value) {
+        Some(flag) => Ok(Some(flag)),
+        None => Err(D::Error::custom(format!(
+            "expected a boolean, found {}",
+            value
+        ))),
+    }
+}
+
+fn flag(value: &Value) -> Option<bool> {
+    match value {
+        Value::Bool(b) => Some(*b),
+        Value::Number(n) => match n.as_u64() {
+            Some(0) => Some(false),
+            Some(1) => Some(true),
+            _ => None,
+        },
+        Value::String(s) => match s.trim().to_lowercase().as_str() {
+            "0" | "false" => Some(false),
+            "1" | "true" => Some(true),
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn string_takes_strings_and_numbers() {
+        assert_eq!(string(json!("abc")).unwrap(), "abc");
+        assert_eq!(string(json!(" ")).unwrap(), " ");
+        assert_eq!(string(json!(2)).unwrap(), "2");
+        assert_eq!(string(json!(2.5)).unwrap(), "2.5");
+        assert!(string(Value::Null).is_err());
+        assert!(string(json!(true)).is_err());
+        assert!(string(json!([])).is_err());
+    }
+
+    #[test]
+    fn option_string_is_none_for_null_and_blank() {
+        assert_eq!(option_string(Value::Null).unwrap(), None);
+        assert_eq!(option_string(json!("")).unwrap(), None);
+        assert_eq!(option_string(json!(" ")).unwrap(), None);
+        assert_eq!(
+            option_string(json!("abc")).unwrap(),
+            Some("abc".to_string())
+        );
+        assert_eq!(option_string(json!(2)).unwrap(), Some("2".to_string()));
+        assert!(option_string(json!(false)).is_err());
+        assert!(option_string(json!({})).is_err());
+    }
+
+    #[test]
+    fn option_parses_numbers_from_strings() {
+        assert_eq!(option::<_, u32>(Value::Null).unwrap(), None);
+        assert_eq!(option::<_, u32>(json!("")).unwrap(), None);
+        assert_eq!(option::<_, u32>(json!(" ")).unwrap(), None);
+        assert_eq!(option::<_, u32>(json!(2)).unwrap(), Some(2));
+        assert_eq!(option::<_, u32>(json!("2")).unwrap(), Some(2));
+        assert_eq!(option::<_, u32>(json!(" 2 ")).unwrap(), Some(2));
+        assert_eq!(option::<_, f64>(json!("2.5")).unwrap(), Some(2.5));
+        assert!(option::<_, u32>(json!("two")).is_err());
+        assert!(option::<_, u32>(json!(-2)).is_err());
+    }
+
+    #[test]
+    fn option_keeps_strings_that_look_like_numbers() {
+        assert_eq!(
+            option::<_, String>(json!("0123")).unwrap(),
+            Some("0123".to_string())
+        );
+        assert_eq!(option::<_, String>(json!(" ")).unwrap(), None);
+    }
+
+    #[test]
+    fn boolean_takes_flags() {
+        assert!(boolean(json!(true)).unwrap());
+        assert!(!boolean(json!(false)).unwrap());
+        assert!(boolean(json!(1)).unwrap());
+        assert!(!boolean(json!(0)).unwrap());
+        assert!(boolean(json!("1")).unwrap());
+        assert!(!boolean(json!("0")).unwrap());
+        assert!(boolean(json!(" TRUE ")).unwrap());
+        assert!(!boolean(json!("false")).unwrap());
+        assert!(boolean(json!(2)).is_err());
+        assert!(boolean(json!(" ")).is_err());
+        assert!(boolean(Value::Null).is_err());
+        assert!(boolean(json!("yes")).is_err());
+    }
+
+    #[test]
+    fn option_boolean_is_none_for_null_and_blank() {
+        assert_eq!(option_boolean(Value::Null).unwrap(), None);
+        assert_eq!(option_boolean(json!("")).unwrap(), None);
+        assert_eq!(option_boolean(json!(" ")).unwrap(), None);
+        assert_eq!(option_boolean(json!("1")).unwrap(), Some(true));
+        assert_eq!(option_boolean(json!(0)).unwrap(), Some(false));
+        assert!(option_boolean(json!(2)).is_err());
+        assert!(option_boolean(json!("2")).is_err());
+    }
+}
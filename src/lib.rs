@@ -1,42 +1,176 @@
 mod article;
-pub use article::Article;
+pub use article::{Article, ListItem as ArticleListItem};
+mod article_type;
+pub use article_type::ArticleType;
 mod customer;
-pub use customer::Customer;
+pub use customer::{Customer, ListItem as CustomerListItem};
 mod currency;
 pub use currency::Currency;
+mod customer_type;
+pub use customer_type::CustomerType;
+mod decimal;
+pub use decimal::{Amount, Quantity};
+mod download;
+pub use download::{Download, DownloadStream};
+mod email_information;
+pub use email_information::EmailInformation;
+mod invoice;
+pub use invoice::{Invoice, ListItem as InvoiceListItem};
+mod label;
+pub use label::Label;
+mod language;
+pub use language::Language;
+mod lenient;
+mod offer;
+pub use offer::{ListItem as OfferListItem, Offer};
 mod order;
-pub use order::{Order, Row as OrderRow};
+pub use order::{ListItem as OrderListItem, Order};
+mod row;
+pub use row::{Row, Row as InvoiceRow, Row as OfferRow, Row as OrderRow};
+mod vat_type;
+pub use vat_type::VatType;
+mod add_article;
 mod add_customer;
+mod add_invoice;
+mod add_offer;
 mod add_order;
 mod api_error_code;
+mod bookkeep_invoice;
+mod cancel_invoice;
+mod cancel_offer;
 mod cancel_order;
+mod create_invoice_from_offer;
+mod create_invoice_from_order;
+mod create_order_from_offer;
+mod credit_invoice;
+mod delete_article;
+mod delete_customer;
+mod edit_article;
+mod edit_customer;
+mod edit_invoice;
+mod edit_offer;
 mod edit_order;
+mod email_invoice;
+mod email_offer;
+mod email_order;
+mod external_print_invoice;
+mod external_print_order;
 mod get_article;
 mod get_customer;
+mod get_invoice;
+mod get_offer;
 mod get_order;
-pub use api_error_code::ApiErrorCode;
+mod list_articles;
+mod list_customers;
+mod list_invoices;
+mod list_offers;
+mod list_orders;
+mod list_query;
+pub use list_query::{
+    ArticleFilter, ArticleSortBy, CustomerFilter, CustomerSortBy, InvoiceFilter, InvoiceSortBy,
+    ListQuery, OfferFilter, OfferSortBy, OrderFilter, OrderSortBy, SortOrder,
+};
+mod mark_invoice_warehouse_ready;
+mod pagination;
+mod preview_invoice;
+mod preview_offer;
+mod preview_order;
+mod print_invoice;
+mod print_offer;
+mod print_order;
+mod sync_articles;
+mod sync_customers;
+mod sync_orders;
+pub use api_error_code::{ApiErrorCategory, ApiErrorCode};
 mod error;
-pub use error::Error;
+pub use error::{Error, ErrorKind};
+mod gateway_builder;
+pub use gateway_builder::GatewayBuilder;
+mod oauth;
+use oauth::Session;
+pub use oauth::{OAuthClient, Scope, TokenSet};
+mod rate_limiter;
+pub use rate_limiter::RateLimiter;
+mod resource;
+pub use resource::Resource;
+mod retry_policy;
+pub use retry_policy::RetryPolicy;
+mod sync_cursor;
+pub use sync_cursor::SyncCursor;
+mod token_store;
+mod validation;
+use futures::future;
+use reqwest::Method;
 use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use std::future::Future;
+use std::sync::Arc;
 use std::time::Duration;
+pub use token_store::{FileTokenStore, InMemoryTokenStore, TokenStore};
 use tokio::time::sleep;
+pub use validation::{FieldError, FieldErrorKind, ValidationMode};
 
 const BASE_URL: &str = "https://api.fortnox.se/3";
-const INITIAL_DELAY_MS: f64 = 100.0;
-const RETRIES: u8 = 5;
-const BACKOFF: f64 = 2.0;
 
+const JSON: &str = "application/json";
+const PDF: &str = "application/pdf";
+
+#[derive(Clone)]
 pub struct Gateway {
     client: reqwest::Client,
+    base_url: String,
+    retry_policy: RetryPolicy,
+    rate_limiter: Option<RateLimiter>,
+    oauth: Option<Arc<Session>>,
 }
 
 impl Gateway {
+    pub fn builder() -> GatewayBuilder {
+        GatewayBuilder::new()
+    }
+
     pub async fn new(
         _client_id: String,
         token: String,
         secret: String,
         timeout: Option<Duration>,
     ) -> Result<Gateway, Error> {
+        let mut builder = GatewayBuilder::new().access_token(token, secret);
+        if let Some(timeout) = timeout {
+            builder = builder.timeout(timeout);
+        }
+        builder.build().await
+    }
+
+    // Creates a gateway that authenticates with OAuth2, see
+    // `GatewayBuilder::oauth`.
+    pub async fn with_oauth(
+        oauth_client: OAuthClient,
+        refresh_token: String,
+        on_refresh: impl Fn(&TokenSet) + Send + Sync + 'static,
+        timeout: Option<Duration>,
+    ) -> Result<Gateway, Error> {
+        let mut builder = GatewayBuilder::new().oauth(oauth_client, refresh_token, on_refresh);
+        if let Some(timeout) = timeout {
+            builder = builder.timeout(timeout);
+        }
+        builder.build().await
+    }
+
+    // Creates a gateway that authenticates with OAuth2 using the tokens in
+    // `store`, see `GatewayBuilder::token_store`.
+    pub async fn with_token_store(
+        oauth_client: OAuthClient,
+        store: Arc<dyn TokenStore>,
+        timeout: Option<Duration>,
+    ) -> Result<Gateway, Error> {
+        let mut builder = GatewayBuilder::new().token_store(oauth_client, store);
+        if let Some(timeout) = timeout {
+            builder = builder.timeout(timeout);
+        }
+        builder.build().await
+    }
+
+    fn default_headers() -> reqwest::header::HeaderMap {
         let mut headers = reqwest::header::HeaderMap::new();
         headers.insert(
             "Content-Type",
@@ -46,426 +180,306 @@ impl Gateway {
             "Accept",
             reqwest::header::HeaderValue::from_static("application/json"),
         );
+        headers
+    }
 
-        let access_token_header = match reqwest::header::HeaderValue::from_str(&token) {
-            Ok(header) => header,
-            Err(err) => {
-                return Err(Error::Unspecified(format!(
-                    "Could not create auth header ({}).",
-                    err.to_string()
-                )))
-            }
-        };
-        headers.insert("Access-Token", access_token_header);
-
-        let client_secret_header = match reqwest::header::HeaderValue::from_str(&secret) {
-            Ok(header) => header,
-            Err(err) => {
-                return Err(Error::Unspecified(format!(
-                    "Could not create auth header ({}).",
-                    err.to_string()
-                )))
-            }
-        };
-        headers.insert("Client-Secret", client_secret_header);
-
-        let timeout = match timeout {
-            Some(t) => t,
-            None => Duration::new(60, 0),
-        };
-
-        let client = match reqwest::ClientBuilder::new()
+    fn build_client(
+        headers: reqwest::header::HeaderMap,
+        https_only: bool,
+        timeout: Duration,
+        user_agent: Option<&str>,
+    ) -> Result<reqwest::Client, Error> {
+        let mut builder = reqwest::ClientBuilder::new()
             .default_headers(headers)
-            .https_only(true)
-            .timeout(timeout)
-            .build()
-        {
-            Ok(r) => r,
-            Err(err) => {
-                return Err(Error::Unspecified(format!(
-                    "Could not create reqwest client ({}).",
-                    err.to_string()
-                )))
-            }
-        };
+            .https_only(https_only)
+            .timeout(timeout);
+        if let Some(user_agent) = user_agent {
+            builder = builder.user_agent(user_agent);
+        }
 
-        let c = Gateway { client };
-        Ok(c)
+        match builder.build() {
+            Ok(r) => Ok(r),
+            Err(err) => Err(Error::unspecified(format!(
+                "Could not create reqwest client ({}).",
+                err
+            ))
+            .with_source(err)),
+        }
     }
 
-    async fn post<'a, T: DeserializeOwned>(
+    async fn http_post<T: DeserializeOwned>(
         &self,
         url: &str,
         body: &impl Serialize,
     ) -> Result<T, Error> {
-        let mut delay = INITIAL_DELAY_MS;
-        for _ in 0..RETRIES {
-            let res = match self.post_without_retry(url, body).await {
-                Ok(res) => res,
-                Err(err) => {
-                    delay = self.randomized_exponential_backoff(delay).await;
-
-                    match err {
-                        Error::Throttling => {
-                            continue;
-                        }
-                        _ => return Err(err),
-                    };
-                }
-            };
+        self.request(Method::POST, url, Some(body), false).await
+    }
 
-            return Ok(res);
-        }
+    async fn http_get<T: DeserializeOwned>(&self, url: &str) -> Result<T, Error> {
+        self.request(Method::GET, url, None::<&()>, true).await
+    }
 
-        Err(Error::Throttling)
+    // A GET that does something besides reading, e.g. sending an e-mail, so it
+    // is retried as carefully as a POST.
+    async fn http_get_with_side_effects<T: DeserializeOwned>(&self, url: &str) -> Result<T, Error> {
+        self.request(Method::GET, url, None::<&()>, false).await
     }
 
-    async fn post_without_retry<'a, T: DeserializeOwned>(
+    async fn http_put<T: DeserializeOwned>(
         &self,
         url: &str,
         body: &impl Serialize,
     ) -> Result<T, Error> {
-        let res = match self.client.post(url).json(body).send().await {
-            Ok(r) => r,
-            Err(err) => {
-                return Err(Error::NetworkError(format!(
-                    "Could not send request ({}).",
-                    err.to_string()
-                )))
-            }
-        };
+        self.request(Method::PUT, url, Some(body), false).await
+    }
 
-        let status = res.status().as_u16();
-        let text = res
-            .text()
+    // Fortnox answers a delete with an empty 204, so there is no body to parse.
+    async fn http_delete(&self, url: &str) -> Result<(), Error> {
+        self.request_with(Method::DELETE, url, None::<&()>, JSON, true, Download::read)
             .await
-            .unwrap_or_else(|_| String::from("Could not retrieve body text."));
-
-        if status < 200 || status > 299 {
-            if status == 429 {
-                return Err(Error::Throttling);
-            }
-
-            #[derive(Deserialize, Debug, Clone, PartialEq)]
-            #[serde(rename_all = "PascalCase")]
-            struct ErrorInformation {
-                pub error_information: ApiError,
-            }
-
-            #[derive(Deserialize, Debug, Clone, PartialEq)]
-            #[serde(rename_all = "PascalCase")]
-            struct ApiError {
-                pub error: u32,
-                pub message: String,
-                pub code: ApiErrorCode,
-            }
-
-            let api_error: ErrorInformation =
-                serde_json::from_str(&text).unwrap_or_else(|_| ErrorInformation {
-                    error_information: ApiError {
-                        error: 0,
-                        message: format!("Unknown error ({}: {})", status, text),
-                        code: ApiErrorCode::Unknown,
-                    },
-                });
-            return Err(Error::ApiError(
-                api_error.error_information.code,
-                api_error.error_information.message,
-            ));
-        }
-
-        let body: T = match serde_json::from_str(&text) {
-            Ok(r) => r,
-            Err(err) => {
-                return Err(Error::SerializationError(format!(
-                    "Could not deserialize response from \"{}\" ({}).",
-                    text,
-                    err.to_string()
-                )))
-            }
-        };
-        Ok(body)
+            .map(|_| ())
     }
 
-    async fn get<'a, T: DeserializeOwned>(&self, url: &str) -> Result<T, Error> {
-        let mut delay = INITIAL_DELAY_MS;
-        for _ in 0..RETRIES {
-            let res = match self.get_without_retry(url).await {
-                Ok(res) => res,
-                Err(err) => {
-                    delay = self.randomized_exponential_backoff(delay).await;
-
-                    match err {
-                        Error::Throttling => {
-                            continue;
-                        }
-                        _ => return Err(err),
-                    };
-                }
-            };
-
-            return Ok(res);
+    async fn request<T: DeserializeOwned>(
+        &self,
+        method: Method,
+        url: &str,
+        body: Option<&impl Serialize>,
+        idempotent: bool,
+    ) -> Result<T, Error> {
+        let download = self
+            .request_with(method.clone(), url, body, JSON, idempotent, Download::read)
+            .await?;
+        match serde_json::from_slice(&download.bytes) {
+            Ok(r) => Ok(r),
+            Err(err) => Err(Error::serialization(format!(
+                "Could not deserialize response from \"{}\" ({}).",
+                String::from_utf8_lossy(&download.bytes),
+                err
+            ))
+            .with_request(&method, url)
+            .with_source(err)),
         }
-
-        Err(Error::Throttling)
     }
 
-    async fn get_without_retry<'a, T: DeserializeOwned>(&self, url: &str) -> Result<T, Error> {
-        let res = match self.client.get(url).send().await {
-            Ok(r) => r,
-            Err(err) => {
-                return Err(Error::NetworkError(format!(
-                    "Could not send request ({}).",
-                    err.to_string()
-                )))
-            }
-        };
-
-        let status = res.status().as_u16();
-        let text = res
-            .text()
+    // Fetches a file, e.g. a PDF, rather than JSON.
+    async fn get_bytes(&self, url: &str, accept: &'static str) -> Result<Download, Error> {
+        self.request_with(Method::GET, url, None::<&()>, accept, true, Download::read)
             .await
-            .unwrap_or_else(|_| String::from("Could not retrieve body text."));
-
-        if status < 200 || status > 299 {
-            if status == 429 {
-                return Err(Error::Throttling);
-            }
-
-            #[derive(Deserialize, Debug, Clone, PartialEq)]
-            #[serde(rename_all = "PascalCase")]
-            struct ErrorInformation {
-                pub error_information: ApiError,
-            }
-
-            #[derive(Deserialize, Debug, Clone, PartialEq)]
-            #[serde(rename_all = "PascalCase")]
-            struct ApiError {
-                pub error: u32,
-                pub message: String,
-                pub code: ApiErrorCode,
-            }
-
-            let api_error: ErrorInformation =
-                serde_json::from_str(&text).unwrap_or_else(|_| ErrorInformation {
-                    error_information: ApiError {
-                        error: 0,
-                        message: format!("Unknown error ({}: {})", status, text),
-                        code: ApiErrorCode::Unknown,
-                    },
-                });
-            return Err(Error::ApiError(
-                api_error.error_information.code,
-                api_error.error_information.message,
-            ));
-        }
+    }
 
-        let body: T = match serde_json::from_str(&text) {
-            Ok(r) => r,
-            Err(err) => {
-                return Err(Error::SerializationError(format!(
-                    "Could not deserialize response from \"{}\" ({}).",
-                    text,
-                    err.to_string()
-                )))
-            }
-        };
-        Ok(body)
+    // Like `get_bytes`, but hands the body over as it arrives. Only getting the
+    // response is retried; the stream fails if the connection breaks.
+    async fn get_stream(&self, url: &str, accept: &'static str) -> Result<DownloadStream, Error> {
+        self.request_with(Method::GET, url, None::<&()>, accept, true, |res| {
+            future::ok(DownloadStream::new(res))
+        })
+        .await
     }
 
-    async fn put<'a, T: DeserializeOwned>(
+    // Sends the request, retrying as the retry policy allows, and passes the
+    // first successful response to `read`. Failing to read it is retried too,
+    // for idempotent requests. A request that is not idempotent, e.g. one that
+    // creates an invoice, is only sent again if it cannot have reached Fortnox,
+    // see `RetryPolicy::is_retryable`.
+    async fn request_with<T, F, Fut>(
         &self,
+        method: Method,
         url: &str,
-        body: &impl Serialize,
-    ) -> Result<T, Error> {
-        let mut delay = INITIAL_DELAY_MS;
-        for _ in 0..RETRIES {
-            let res = match self.put_without_retry(url, body).await {
-                Ok(res) => res,
-                Err(err) => {
-                    delay = self.randomized_exponential_backoff(delay).await;
-
-                    match err {
-                        Error::Throttling => {
-                            continue;
+        body: Option<&impl Serialize>,
+        accept: &'static str,
+        idempotent: bool,
+        read: F,
+    ) -> Result<T, Error>
+    where
+        F: Fn(reqwest::Response) -> Fut,
+        Fut: Future<Output = Result<T, Error>>,
+    {
+        let mut retries: u32 = 0;
+        let mut reauthorized = false;
+        loop {
+            if let Some(rate_limiter) = &self.rate_limiter {
+                rate_limiter.acquire().await;
+            }
+
+            let access_token = match &self.oauth {
+                Some(oauth) => oauth.access_token().await.map(Some),
+                None => Ok(None),
+            };
+            let (err, retry_after, access_token, mut sent) = match access_token {
+                Ok(access_token) => match self
+                    .request_without_retry(
+                        method.clone(),
+                        url,
+                        body,
+                        accept,
+                        access_token.as_deref(),
+                    )
+                    .await
+                {
+                    Ok(res) => {
+                        let retry_after = retry_after(res.headers());
+                        match read(res).await {
+                            Ok(r) => return Ok(r),
+                            Err(err) => (err, retry_after, access_token, true),
                         }
-                        _ => return Err(err),
-                    };
+                    }
+                    Err((err, retry_after)) => (err, retry_after, access_token, true),
+                },
+                Err(err) => (err, None, None, false),
+            };
+            let mut err = err.with_request(&method, url);
+
+            // The access token was rejected even though it had not expired
+            // (e.g. it was revoked); refresh it once and try again right away.
+            if let (ErrorKind::Unauthorized, Some(oauth), Some(access_token), false) =
+                (err.kind(), &self.oauth, &access_token, reauthorized)
+            {
+                reauthorized = true;
+                match oauth.invalidate(access_token).await {
+                    Ok(()) => continue,
+                    // Fortnox turned the request away, so it was not carried out
+                    // and only the refresh needs to succeed.
+                    Err(refresh_err) => {
+                        err = refresh_err.with_request(&method, url);
+                        sent = false;
+                    }
                 }
+            }
+
+            // A request that did not get as far as Fortnox, for want of an access
+            // token, is as safe to retry as a GET.
+            let retryable = if sent {
+                self.retry_policy.is_retryable(&method, idempotent, &err)
+            } else {
+                self.retry_policy.is_retryable_unsent(&err)
             };
+            retries += 1;
+            if retries >= u32::from(self.retry_policy.max_attempts) || !retryable {
+                return Err(err);
+            }
 
-            return Ok(res);
+            sleep(self.retry_policy.delay(retries, retry_after)).await;
         }
-
-        Err(Error::Throttling)
     }
 
-    async fn put_without_retry<'a, T: DeserializeOwned>(
+    // Returns the response if it was successful, leaving the body unread. On
+    // failure, also returns how long the response asked us to wait before trying
+    // again, if it did.
+    async fn request_without_retry(
         &self,
+        method: Method,
         url: &str,
-        body: &impl Serialize,
-    ) -> Result<T, Error> {
-        let res = match self.client.put(url).json(body).send().await {
+        body: Option<&impl Serialize>,
+        accept: &'static str,
+        access_token: Option<&str>,
+    ) -> Result<reqwest::Response, (Error, Option<Duration>)> {
+        let mut req = self
+            .client
+            .request(method, url)
+            .header(reqwest::header::ACCEPT, accept);
+        if let Some(body) = body {
+            req = req.json(body);
+        }
+        if let Some(access_token) = access_token {
+            req = req.bearer_auth(access_token);
+        }
+
+        let res = match req.send().await {
             Ok(r) => r,
             Err(err) => {
-                return Err(Error::NetworkError(format!(
-                    "Could not send request ({}).",
-                    err.to_string()
-                )))
+                let connect = err.is_connect();
+                return Err((
+                    Error::network(format!("Could not send request ({}).", err))
+                        .with_connect(connect)
+                        .with_source(err),
+                    None,
+                ));
             }
         };
 
         let status = res.status().as_u16();
-        let text = res
-            .text()
-            .await
-            .unwrap_or_else(|_| String::from("Could not retrieve body text."));
-
-        if status < 200 || status > 299 {
-            if status == 429 {
-                return Err(Error::Throttling);
-            }
+        if (200..=299).contains(&status) {
+            return Ok(res);
+        }
 
-            #[derive(Deserialize, Debug, Clone, PartialEq)]
-            #[serde(rename_all = "PascalCase")]
-            struct ErrorInformation {
-                pub error_information: ApiError,
+        let retry_after = retry_after(res.headers());
+        let bytes = match res.bytes().await {
+            Ok(bytes) => bytes,
+            Err(err) => {
+                return Err((
+                    Error::network(format!("Could not retrieve body ({}).", err)).with_source(err),
+                    retry_after,
+                ))
             }
+        };
+        let text = String::from_utf8_lossy(&bytes).into_owned();
 
-            #[derive(Deserialize, Debug, Clone, PartialEq)]
-            #[serde(rename_all = "PascalCase")]
-            struct ApiError {
-                pub error: u32,
-                pub message: String,
-                pub code: ApiErrorCode,
-            }
+        if status == 429 {
+            return Err((
+                Error::throttling().with_response(Some(status), text),
+                retry_after,
+            ));
+        }
 
-            let api_error: ErrorInformation =
-                serde_json::from_str(&text).unwrap_or_else(|_| ErrorInformation {
-                    error_information: ApiError {
-                        error: 0,
-                        message: format!("Unknown error ({}: {})", status, text),
-                        code: ApiErrorCode::Unknown,
-                    },
-                });
-            return Err(Error::ApiError(
-                api_error.error_information.code,
-                api_error.error_information.message,
+        if status == 401 && access_token.is_some() {
+            return Err((
+                Error::unauthorized(format!("Access token was rejected ({}).", text))
+                    .with_response(Some(status), text),
+                None,
             ));
         }
 
-        let body: T = match serde_json::from_str(&text) {
-            Ok(r) => r,
-            Err(err) => {
-                return Err(Error::SerializationError(format!(
-                    "Could not deserialize response from \"{}\" ({}).",
-                    text,
-                    err.to_string()
-                )))
+        // Fortnox has sent these fields both capitalized and not, and the code
+        // both as a number and as a string.
+        #[derive(Deserialize, Debug, Clone, PartialEq)]
+        #[serde(rename_all = "PascalCase")]
+        struct ErrorInformation {
+            #[serde(alias = "errorInformation")]
+            pub error_information: ApiError,
+        }
+
+        #[derive(Deserialize, Debug, Clone, PartialEq)]
+        #[serde(rename_all = "PascalCase")]
+        struct ApiError {
+            #[serde(alias = "message")]
+            pub message: String,
+            #[serde(alias = "code")]
+            #[serde(default)]
+            #[serde(deserialize_with = "lenient::option")]
+            pub code: Option<u32>,
+        }
+
+        let err = match serde_json::from_str::<ErrorInformation>(&text) {
+            Ok(info) => match info.error_information.code {
+                Some(code) => Error::api(code, info.error_information.message),
+                None => Error::new(ErrorKind::Api, info.error_information.message),
+            },
+            // Typically a gateway or load balancer failing in front of Fortnox.
+            Err(_) if status >= 500 => {
+                Error::server(format!("Server error ({}: {})", status, text))
             }
+            Err(_) => Error::new(
+                ErrorKind::Api,
+                format!("Unknown error ({}: {})", status, text),
+            ),
         };
-        Ok(body)
+        Err((err.with_response(Some(status), text), retry_after))
     }
+}
 
-    // async fn delete<'a, T: DeserializeOwned>(&self, url: &str) -> Result<T, Error> {
-    //     let mut delay = INITIAL_DELAY_MS;
-    //     for _ in 0..RETRIES {
-    //         let res = match self.delete_without_retry(url).await {
-    //             Ok(res) => res,
-    //             Err(err) => {
-    //                 delay = self.randomized_exponential_backoff(delay).await;
-
-    //                 match err {
-    //                     Error::Throttling => {
-    //                         continue;
-    //                     }
-    //                     _ => return Err(err),
-    //                 };
-    //             }
-    //         };
-
-    //         return Ok(res);
-    //     }
-
-    //     Err(Error::Throttling)
-    // }
-
-    // async fn delete_without_retry<'a, T: DeserializeOwned>(&self, url: &str) -> Result<T, Error> {
-    //     let res = match self.client.delete(url).send().await {
-    //         Ok(r) => r,
-    //         Err(err) => {
-    //             return Err(Error::NetworkError(format!(
-    //                 "Could not send request ({}).",
-    //                 err.to_string()
-    //             )))
-    //         }
-    //     };
-
-    //     let status = res.status().as_u16();
-    //     let text = res
-    //         .text()
-    //         .await
-    //         .unwrap_or_else(|_| String::from("Could not retrieve body text."));
-
-    //     if status < 200 || status > 299 {
-    //         if status == 429 {
-    //             return Err(Error::Throttling);
-    //         }
-
-    //         #[derive(Deserialize, Debug, Clone, PartialEq)]
-    //         #[serde(rename_all = "PascalCase")]
-    //         struct ErrorInformation {
-    //             pub error_information: ApiError,
-    //         }
-
-    //         #[derive(Deserialize, Debug, Clone, PartialEq)]
-    //         #[serde(rename_all = "PascalCase")]
-    //         struct ApiError {
-    //             pub error: u32,
-    //             pub message: String,
-    //             pub code: ApiErrorCode,
-    //         }
-
-    //         let api_error: ErrorInformation =
-    //             serde_json::from_str(&text).unwrap_or_else(|_| ErrorInformation {
-    //                 error_information: ApiError {
-    //                     error: 0,
-    //                     message: format!("Unknown error ({}: {})", status, text),
-    //                     code: ApiErrorCode::Unknown,
-    //                 },
-    //             });
-    //         return Err(Error::ApiError(
-    //             api_error.error_information.code,
-    //             api_error.error_information.message,
-    //         ));
-    //     }
-
-    //     let body: T = match serde_json::from_str(&text) {
-    //         Ok(r) => r,
-    //         Err(err) => {
-    //             return Err(Error::SerializationError(format!(
-    //                 "Could not deserialize response from \"{}\" ({}).",
-    //                 text,
-    //                 err.to_string()
-    //             )))
-    //         }
-    //     };
-    //     Ok(body)
-    // }
-
-    // Randomized exponential backoff policy (cf.
-    // https://cloud.google.com/appengine/articles/scalability#backoff ).
-    async fn randomized_exponential_backoff(&self, mut delay_ms: f64) -> f64 {
-        //let mut rng = rand::thread_rng();
-
-        // Random component to avoid thundering herd problem (values taken from
-        // https://github.com/GoogleCloudPlatform/appengine-gcs-client/blob/master/java/src/main/
-        // java/com/google/appengine/tools/cloudstorage/RetryHelper.java ).
-        //delay_ms = (rng.gen::<f64>() / 2.0 + 0.75) * delay_ms;
-
-        sleep(Duration::from_millis(delay_ms as u64)).await;
-
-        delay_ms *= BACKOFF;
-        delay_ms
+// Parses a Retry-After header, given either in seconds or as an HTTP date.
+fn retry_after(headers: &reqwest::header::HeaderMap) -> Option<Duration> {
+    let value = headers
+        .get(reqwest::header::RETRY_AFTER)?
+        .to_str()
+        .ok()?
+        .trim();
+    if let Ok(seconds) = value.parse::<u64>() {
+        return Some(Duration::from_secs(seconds));
     }
+    let at = chrono::DateTime::parse_from_rfc2822(value).ok()?;
+    (at.with_timezone(&chrono::Utc) - chrono::Utc::now())
+        .to_std()
+        .ok()
 }
@@ -0,0 +1,18 @@
+use crate::{Article, ArticleListItem, Error, Gateway, ListQuery, Resource};
+use futures::stream::Stream;
+
+impl Gateway {
+    pub async fn list_articles(
+        &self,
+        query: &ListQuery<Article>,
+    ) -> Result<Vec<ArticleListItem>, Error> {
+        self.list(query).await
+    }
+
+    pub fn stream_articles(
+        &self,
+        query: &ListQuery<Article>,
+    ) -> impl Stream<Item = Result<ArticleListItem, Error>> + '_ {
+        self.paginate(Article::PATH, Article::LIST_KEY, query)
+    }
+}
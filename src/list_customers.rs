@@ -0,0 +1,18 @@
+use crate::{Customer, CustomerListItem, Error, Gateway, ListQuery, Resource};
+use futures::stream::Stream;
+
+impl Gateway {
+    pub async fn list_customers(
+        &self,
+        query: &ListQuery<Customer>,
+    ) -> Result<Vec<CustomerListItem>, Error> {
+        self.list(query).await
+    }
+
+    pub fn stream_customers(
+        &self,
+        query: &ListQuery<Customer>,
+    ) -> impl Stream<Item = Result<CustomerListItem, Error>> + '_ {
+        self.paginate(Customer::PATH, Customer::LIST_KEY, query)
+    }
+}
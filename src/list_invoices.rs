@@ -0,0 +1,18 @@
+use crate::{Error, Gateway, Invoice, InvoiceListItem, ListQuery, Resource};
+use futures::stream::Stream;
+
+impl Gateway {
+    pub async fn list_invoices(
+        &self,
+        query: &ListQuery<Invoice>,
+    ) -> Result<Vec<InvoiceListItem>, Error> {
+        self.list(query).await
+    }
+
+    pub fn stream_invoices(
+        &self,
+        query: &ListQuery<Invoice>,
+    ) -> impl Stream<Item = Result<InvoiceListItem, Error>> + '_ {
+        self.paginate(Invoice::PATH, Invoice::LIST_KEY, query)
+    }
+}
@@ -0,0 +1,15 @@
+use crate::{Error, Gateway, ListQuery, Offer, OfferListItem, Resource};
+use futures::stream::Stream;
+
+impl Gateway {
+    pub async fn list_offers(&self, query: &ListQuery<Offer>) -> Result<Vec<OfferListItem>, Error> {
+        self.list(query).await
+    }
+
+    pub fn stream_offers(
+        &self,
+        query: &ListQuery<Offer>,
+    ) -> impl Stream<Item = Result<OfferListItem, Error>> + '_ {
+        self.paginate(Offer::PATH, Offer::LIST_KEY, query)
+    }
+}
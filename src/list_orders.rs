@@ -0,0 +1,15 @@
+use crate::{Error, Gateway, ListQuery, Order, OrderListItem, Resource};
+use futures::stream::Stream;
+
+impl Gateway {
+    pub async fn list_orders(&self, query: &ListQuery<Order>) -> Result<Vec<OrderListItem>, Error> {
+        self.list(query).await
+    }
+
+    pub fn stream_orders(
+        &self,
+        query: &ListQuery<Order>,
+    ) -> impl Stream<Item = Result<OrderListItem, Error>> + '_ {
+        self.paginate(Order::PATH, Order::LIST_KEY, query)
+    }
+}
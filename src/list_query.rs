@@ -0,0 +1,437 @@
+use crate::{Article, Customer, Error, Invoice, Offer, Order};
+use chrono::{NaiveDate, NaiveDateTime};
+use std::marker::PhantomData;
+
+// Fortnox refuses pages larger than this.
+const MAX_LIMIT: u32 = 500;
+
+// Filters, sorting and search for a list endpoint. The resource type decides
+// which filters and search fields are available, e.g. only documents such as
+// orders can be limited to a date range.
+#[derive(Debug)]
+pub struct ListQuery<R> {
+    params: Vec<(&'static str, String)>,
+    limit: Option<u32>,
+    resource: PhantomData<fn() -> R>,
+}
+
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum SortOrder {
+    Ascending,
+    Descending,
+}
+
+impl SortOrder {
+    fn as_str(&self) -> &'static str {
+        match self {
+            SortOrder::Ascending => "ascending",
+            SortOrder::Descending => "descending",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum CustomerFilter {
+    Active,
+    Inactive,
+}
+
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum CustomerSortBy {
+    CustomerNumber,
+    Name,
+}
+
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum OrderFilter {
+    Cancelled,
+    Expired,
+    InvoiceCreated,
+    InvoiceNotCreated,
+}
+
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum OrderSortBy {
+    CustomerName,
+    CustomerNumber,
+    DocumentNumber,
+    OrderDate,
+}
+
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum OfferFilter {
+    Cancelled,
+    Completed,
+    Expired,
+    NotCompleted,
+    OrderCreated,
+    OrderNotCreated,
+}
+
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum OfferSortBy {
+    CustomerName,
+    CustomerNumber,
+    DocumentNumber,
+    OfferDate,
+}
+
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum InvoiceFilter {
+    Cancelled,
+    FullyPaid,
+    Unbooked,
+    Unpaid,
+    UnpaidOverdue,
+}
+
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum InvoiceSortBy {
+    CustomerName,
+    CustomerNumber,
+    DocumentNumber,
+    InvoiceDate,
+    Ocr,
+    Total,
+}
+
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum ArticleFilter {
+    Active,
+    Inactive,
+}
+
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum ArticleSortBy {
+    ArticleNumber,
+    Description,
+    QuantityInStock,
+}
+
+impl<R> ListQuery<R> {
+    pub fn new() -> ListQuery<R> {
+        ListQuery {
+            params: vec![],
+            limit: None,
+            resource: PhantomData,
+        }
+    }
+
+    // Only records changed since `last_modified`, given in Swedish local time
+    // like every timestamp in Fortnox. Fortnox only looks at the minute.
+    pub fn last_modified(self, last_modified: NaiveDateTime) -> ListQuery<R> {
+        self.param(
+            "lastmodified",
+            last_modified.format("%Y-%m-%d %H:%M").to_string(),
+        )
+    }
+
+    pub fn sort_order(self, sort_order: SortOrder) -> ListQuery<R> {
+        self.param("sortorder", sort_order.as_str().to_string())
+    }
+
+    // The number of records per page, at most 500. The list endpoints still
+    // walk every page.
+    pub fn limit(mut self, limit: u32) -> ListQuery<R> {
+        self.limit = Some(limit);
+        self
+    }
+
+    // Skips this many records before the first page.
+    pub fn offset(self, offset: u32) -> ListQuery<R> {
+        self.param("offset", offset.to_string())
+    }
+
+    fn param(mut self, name: &'static str, value: String) -> ListQuery<R> {
+        // A later call replaces an earlier one rather than repeating the parameter.
+        self.params.retain(|(n, _)| *n != name);
+        self.params.push((name, value));
+        self
+    }
+
+    pub(crate) fn to_query(&self) -> Result<Vec<(&'static str, String)>, Error> {
+        let limit = match self.limit {
+            Some(limit) if limit == 0 || limit > MAX_LIMIT => {
+                return Err(Error::parse(format!(
+                    "Limit must be between 1 and {} ({}).",
+                    MAX_LIMIT, limit
+                )))
+            }
+            Some(limit) => limit,
+            None => MAX_LIMIT,
+        };
+
+        let mut params = self.params.clone();
+        params.push(("limit", limit.to_string()));
+        Ok(params)
+    }
+}
+
+// Not derived, since that would require the resource type to be Clone too.
+impl<R> Clone for ListQuery<R> {
+    fn clone(&self) -> ListQuery<R> {
+        ListQuery {
+            params: self.params.clone(),
+            limit: self.limit,
+            resource: PhantomData,
+        }
+    }
+}
+
+impl<R> Default for ListQuery<R> {
+    fn default() -> ListQuery<R> {
+        ListQuery::new()
+    }
+}
+
+impl ListQuery<Customer> {
+    pub fn filter(self, filter: CustomerFilter) -> ListQuery<Customer> {
+        let filter = match filter {
+            CustomerFilter::Active => "active",
+            CustomerFilter::Inactive => "inactive",
+        };
+        self.param("filter", filter.to_string())
+    }
+
+    pub fn sort_by(self, sort_by: CustomerSortBy) -> ListQuery<Customer> {
+        let sort_by = match sort_by {
+            CustomerSortBy::CustomerNumber => "customernumber",
+            CustomerSortBy::Name => "name",
+        };
+        self.param("sortby", sort_by.to_string())
+    }
+
+    pub fn customer_number(self, customer_number: impl Into<String>) -> ListQuery<Customer> {
+        self.param("customernumber", customer_number.into())
+    }
+
+    pub fn name(self, name: impl Into<String>) -> ListQuery<Customer> {
+        self.param("name", name.into())
+    }
+
+    pub fn email(self, email: impl Into<String>) -> ListQuery<Customer> {
+        self.param("email", email.into())
+    }
+
+    pub fn organisation_number(
+        self,
+        organisation_number: impl Into<String>,
+    ) -> ListQuery<Customer> {
+        self.param("organisationnumber", organisation_number.into())
+    }
+
+    pub fn city(self, city: impl Into<String>) -> ListQuery<Customer> {
+        self.param("city", city.into())
+    }
+
+    pub fn zip_code(self, zip_code: impl Into<String>) -> ListQuery<Customer> {
+        self.param("zipcode", zip_code.into())
+    }
+
+    pub fn phone(self, phone: impl Into<String>) -> ListQuery<Customer> {
+        self.param("phone", phone.into())
+    }
+}
+
+impl ListQuery<Order> {
+    pub fn filter(self, filter: OrderFilter) -> ListQuery<Order> {
+        let filter = match filter {
+            OrderFilter::Cancelled => "cancelled",
+            OrderFilter::Expired => "expired",
+            OrderFilter::InvoiceCreated => "invoicecreated",
+            OrderFilter::InvoiceNotCreated => "invoicenotcreated",
+        };
+        self.param("filter", filter.to_string())
+    }
+
+    pub fn sort_by(self, sort_by: OrderSortBy) -> ListQuery<Order> {
+        let sort_by = match sort_by {
+            OrderSortBy::CustomerName => "customername",
+            OrderSortBy::CustomerNumber => "customernumber",
+            OrderSortBy::DocumentNumber => "documentnumber",
+            OrderSortBy::OrderDate => "orderdate",
+        };
+        self.param("sortby", sort_by.to_string())
+    }
+
+    pub fn from_date(self, from_date: NaiveDate) -> ListQuery<Order> {
+        self.param("fromdate", from_date.format("%Y-%m-%d").to_string())
+    }
+
+    pub fn to_date(self, to_date: NaiveDate) -> ListQuery<Order> {
+        self.param("todate", to_date.format("%Y-%m-%d").to_string())
+    }
+
+    pub fn customer_number(self, customer_number: impl Into<String>) -> ListQuery<Order> {
+        self.param("customernumber", customer_number.into())
+    }
+
+    pub fn customer_name(self, customer_name: impl Into<String>) -> ListQuery<Order> {
+        self.param("customername", customer_name.into())
+    }
+
+    pub fn document_number(self, document_number: impl Into<String>) -> ListQuery<Order> {
+        self.param("documentnumber", document_number.into())
+    }
+
+    pub fn external_invoice_reference1(self, reference: impl Into<String>) -> ListQuery<Order> {
+        self.param("externalinvoicereference1", reference.into())
+    }
+
+    pub fn external_invoice_reference2(self, reference: impl Into<String>) -> ListQuery<Order> {
+        self.param("externalinvoicereference2", reference.into())
+    }
+
+    pub fn your_reference(self, your_reference: impl Into<String>) -> ListQuery<Order> {
+        self.param("yourreference", your_reference.into())
+    }
+}
+
+impl ListQuery<Offer> {
+    pub fn filter(self, filter: OfferFilter) -> ListQuery<Offer> {
+        let filter = match filter {
+            OfferFilter::Cancelled => "cancelled",
+            OfferFilter::Completed => "completed",
+            OfferFilter::Expired => "expired",
+            OfferFilter::NotCompleted => "notcompleted",
+            OfferFilter::OrderCreated => "ordercreated",
+            OfferFilter::OrderNotCreated => "ordernotcreated",
+        };
+        self.param("filter", filter.to_string())
+    }
+
+    pub fn sort_by(self, sort_by: OfferSortBy) -> ListQuery<Offer> {
+        let sort_by = match sort_by {
+            OfferSortBy::CustomerName => "customername",
+            OfferSortBy::CustomerNumber => "customernumber",
+            OfferSortBy::DocumentNumber => "documentnumber",
+            OfferSortBy::OfferDate => "offerdate",
+        };
+        self.param("sortby", sort_by.to_string())
+    }
+
+    pub fn from_date(self, from_date: NaiveDate) -> ListQuery<Offer> {
+        self.param("fromdate", from_date.format("%Y-%m-%d").to_string())
+    }
+
+    pub fn to_date(self, to_date: NaiveDate) -> ListQuery<Offer> {
+        self.param("todate", to_date.format("%Y-%m-%d").to_string())
+    }
+
+    pub fn customer_number(self, customer_number: impl Into<String>) -> ListQuery<Offer> {
+        self.param("customernumber", customer_number.into())
+    }
+
+    pub fn customer_name(self, customer_name: impl Into<String>) -> ListQuery<Offer> {
+        self.param("customername", customer_name.into())
+    }
+
+    pub fn document_number(self, document_number: impl Into<String>) -> ListQuery<Offer> {
+        self.param("documentnumber", document_number.into())
+    }
+
+    pub fn your_reference(self, your_reference: impl Into<String>) -> ListQuery<Offer> {
+        self.param("yourreference", your_reference.into())
+    }
+}
+
+impl ListQuery<Invoice> {
+    pub fn filter(self, filter: InvoiceFilter) -> ListQuery<Invoice> {
+        let filter = match filter {
+            InvoiceFilter::Cancelled => "cancelled",
+            InvoiceFilter::FullyPaid => "fullypaid",
+            InvoiceFilter::Unbooked => "unbooked",
+            InvoiceFilter::Unpaid => "unpaid",
+            InvoiceFilter::UnpaidOverdue => "unpaidoverdue",
+        };
+        self.param("filter", filter.to_string())
+    }
+
+    pub fn sort_by(self, sort_by: InvoiceSortBy) -> ListQuery<Invoice> {
+        let sort_by = match sort_by {
+            InvoiceSortBy::CustomerName => "customername",
+            InvoiceSortBy::CustomerNumber => "customernumber",
+            InvoiceSortBy::DocumentNumber => "documentnumber",
+            InvoiceSortBy::InvoiceDate => "invoicedate",
+            InvoiceSortBy::Ocr => "ocr",
+            InvoiceSortBy::Total => "total",
+        };
+        self.param("sortby", sort_by.to_string())
+    }
+
+    // Invoices dated from `from_date` up to and including `to_date`.
+    pub fn from_date(self, from_date: NaiveDate) -> ListQuery<Invoice> {
+        self.param("fromdate", from_date.format("%Y-%m-%d").to_string())
+    }
+
+    pub fn to_date(self, to_date: NaiveDate) -> ListQuery<Invoice> {
+        self.param("todate", to_date.format("%Y-%m-%d").to_string())
+    }
+
+    pub fn customer_number(self, customer_number: impl Into<String>) -> ListQuery<Invoice> {
+        self.param("customernumber", customer_number.into())
+    }
+
+    pub fn customer_name(self, customer_name: impl Into<String>) -> ListQuery<Invoice> {
+        self.param("customername", customer_name.into())
+    }
+
+    pub fn document_number(self, document_number: impl Into<String>) -> ListQuery<Invoice> {
+        self.param("documentnumber", document_number.into())
+    }
+
+    pub fn ocr(self, ocr: impl Into<String>) -> ListQuery<Invoice> {
+        self.param("ocr", ocr.into())
+    }
+
+    pub fn external_invoice_reference1(self, reference: impl Into<String>) -> ListQuery<Invoice> {
+        self.param("externalinvoicereference1", reference.into())
+    }
+
+    pub fn external_invoice_reference2(self, reference: impl Into<String>) -> ListQuery<Invoice> {
+        self.param("externalinvoicereference2", reference.into())
+    }
+
+    pub fn your_reference(self, your_reference: impl Into<String>) -> ListQuery<Invoice> {
+        self.param("yourreference", your_reference.into())
+    }
+}
+
+impl ListQuery<Article> {
+    pub fn filter(self, filter: ArticleFilter) -> ListQuery<Article> {
+        let filter = match filter {
+            ArticleFilter::Active => "active",
+            ArticleFilter::Inactive => "inactive",
+        };
+        self.param("filter", filter.to_string())
+    }
+
+    pub fn sort_by(self, sort_by: ArticleSortBy) -> ListQuery<Article> {
+        let sort_by = match sort_by {
+            ArticleSortBy::ArticleNumber => "articlenumber",
+            ArticleSortBy::Description => "description",
+            ArticleSortBy::QuantityInStock => "quantityinstock",
+        };
+        self.param("sortby", sort_by.to_string())
+    }
+
+    pub fn article_number(self, article_number: impl Into<String>) -> ListQuery<Article> {
+        self.param("articlenumber", article_number.into())
+    }
+
+    pub fn description(self, description: impl Into<String>) -> ListQuery<Article> {
+        self.param("description", description.into())
+    }
+
+    pub fn ean(self, ean: impl Into<String>) -> ListQuery<Article> {
+        self.param("ean", ean.into())
+    }
+
+    pub fn supplier_number(self, supplier_number: impl Into<String>) -> ListQuery<Article> {
+        self.param("suppliernumber", supplier_number.into())
+    }
+
+    pub fn manufacturer(self, manufacturer: impl Into<String>) -> ListQuery<Article> {
+        self.param("manufacturer", manufacturer.into())
+    }
+}
@@ -0,0 +1,424 @@
+use crate::{Error, ErrorKind, Gateway, TokenStore};
+use chrono::{DateTime, Duration, TimeZone, Utc};
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+use std::time::Duration as StdDuration;
+use tokio::sync::Mutex;
+use tokio::time::sleep;
+
+const AUTH_URL: &str = "https://apps.fortnox.se/oauth-v1/auth";
+const TOKEN_URL: &str = "https://apps.fortnox.se/oauth-v1/token";
+
+// Access tokens are refreshed this long before they actually expire, so that a
+// token never runs out while a request is in flight.
+const EXPIRY_MARGIN_SECS: i64 = 60;
+
+const TOKEN_TIMEOUT_SECS: u64 = 60;
+
+const STORE_RETRIES: u8 = 5;
+const STORE_RETRY_DELAY_MS: u64 = 200;
+
+#[derive(Debug, Clone)]
+pub struct OAuthClient {
+    pub client_id: String,
+    pub client_secret: String,
+    pub auth_url: String,
+    pub token_url: String,
+
+    // Whether a plain http `token_url` is refused. Defaults to true; turn it off
+    // to point the client at a local stand-in.
+    pub https_only: bool,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, Eq, PartialEq, Hash)]
+#[serde(rename_all = "lowercase")]
+pub enum Scope {
+    Archive,
+    Article,
+    Bookkeeping,
+    CompanyInformation,
+    ConnectFile,
+    CostCenter,
+    Currency,
+    Customer,
+    Inbox,
+    Invoice,
+    Offer,
+    Order,
+    Payment,
+    Price,
+    Print,
+    Profile,
+    Project,
+    Settings,
+    Supplier,
+    SupplierInvoice,
+}
+
+impl Scope {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Scope::Archive => "archive",
+            Scope::Article => "article",
+            Scope::Bookkeeping => "bookkeeping",
+            Scope::CompanyInformation => "companyinformation",
+            Scope::ConnectFile => "connectfile",
+            Scope::CostCenter => "costcenter",
+            Scope::Currency => "currency",
+            Scope::Customer => "customer",
+            Scope::Inbox => "inbox",
+            Scope::Invoice => "invoice",
+            Scope::Offer => "offer",
+            Scope::Order => "order",
+            Scope::Payment => "payment",
+            Scope::Price => "price",
+            Scope::Print => "print",
+            Scope::Profile => "profile",
+            Scope::Project => "project",
+            Scope::Settings => "settings",
+            Scope::Supplier => "supplier",
+            Scope::SupplierInvoice => "supplierinvoice",
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct TokenSet {
+    pub access_token: String,
+    pub refresh_token: String,
+    pub scope: String,
+    pub expires_at: DateTime<Utc>,
+}
+
+impl TokenSet {
+    // A token set that only holds a refresh token, e.g. to seed a token store.
+    // The access token is fetched the first time it is needed.
+    pub fn from_refresh_token(refresh_token: String) -> TokenSet {
+        TokenSet {
+            access_token: String::new(),
+            refresh_token,
+            scope: String::new(),
+            expires_at: Utc.timestamp_opt(0, 0).unwrap(),
+        }
+    }
+
+    pub fn is_expired(&self) -> bool {
+        Utc::now() + Duration::seconds(EXPIRY_MARGIN_SECS) >= self.expires_at
+    }
+}
+
+impl OAuthClient {
+    pub fn new(client_id: String, client_secret: String) -> OAuthClient {
+        OAuthClient {
+            client_id,
+            client_secret,
+            auth_url: AUTH_URL.to_string(),
+            token_url: TOKEN_URL.to_string(),
+            https_only: true,
+        }
+    }
+
+    // The URL to send a user to in order to authorize the integration. Fortnox
+    // redirects back to `redirect_uri` with `code` and `state` query parameters;
+    // pass the code to `exchange_code`. A `service` account authorizes the
+    // integration for the company rather than for the user who signs in.
+    pub fn authorization_url(
+        &self,
+        redirect_uri: &str,
+        scopes: &[Scope],
+        state: &str,
+        service: bool,
+    ) -> Result<String, Error> {
+        let scope = scopes
+            .iter()
+            .map(|scope| scope.as_str())
+            .collect::<Vec<_>>()
+            .join(" ");
+
+        let mut params = vec![
+            ("client_id", self.client_id.as_str()),
+            ("redirect_uri", redirect_uri),
+            ("scope", scope.as_str()),
+            ("state", state),
+            ("access_type", "offline"),
+            ("response_type", "code"),
+        ];
+        if service {
+            params.push(("account_type", "service"));
+        }
+
+        match reqwest::Url::parse_with_params(&self.auth_url, &params) {
+            Ok(url) => Ok(url.to_string()),
+            Err(err) => Err(Error::parse(format!(
+                "Could not build authorization URL from \"{}\" ({}).",
+                self.auth_url, err
+            ))
+            .with_source(err)),
+        }
+    }
+
+    // Exchanges the code Fortnox passed to the redirect URI for a token set,
+    // which can then be saved to a `TokenStore`. `redirect_uri` must be the same
+    // as in the authorization URL.
+    pub async fn exchange_code(&self, code: &str, redirect_uri: &str) -> Result<TokenSet, Error> {
+        let http = Gateway::build_client(
+            reqwest::header::HeaderMap::new(),
+            self.https_only,
+            StdDuration::from_secs(TOKEN_TIMEOUT_SECS),
+            None,
+        )?;
+        self.request_token(
+            &http,
+            &[
+                ("grant_type", "authorization_code"),
+                ("code", code),
+                ("redirect_uri", redirect_uri),
+            ],
+        )
+        .await
+    }
+
+    pub(crate) async fn refresh(
+        &self,
+        http: &reqwest::Client,
+        refresh_token: &str,
+    ) -> Result<TokenSet, Error> {
+        self.request_token(
+            http,
+            &[
+                ("grant_type", "refresh_token"),
+                ("refresh_token", refresh_token),
+            ],
+        )
+        .await
+    }
+
+    async fn request_token(
+        &self,
+        http: &reqwest::Client,
+        params: &[(&str, &str)],
+    ) -> Result<TokenSet, Error> {
+        let res = match http
+            .post(&self.token_url)
+            .basic_auth(&self.client_id, Some(&self.client_secret))
+            .form(params)
+            .send()
+            .await
+        {
+            Ok(r) => r,
+            Err(err) => {
+                let connect = err.is_connect();
+                return Err(
+                    Error::network(format!("Could not send token request ({}).", err))
+                        .with_connect(connect)
+                        .with_source(err),
+                );
+            }
+        };
+
+        let status = res.status().as_u16();
+        let text = res
+            .text()
+            .await
+            .unwrap_or_else(|_| String::from("Could not retrieve body text."));
+
+        if !(200..=299).contains(&status) {
+            if status == 429 {
+                return Err(Error::throttling().with_response(Some(status), text));
+            }
+
+            #[derive(Deserialize)]
+            struct TokenError {
+                error: String,
+                #[serde(default)]
+                error_description: Option<String>,
+            }
+
+            let message = match serde_json::from_str::<TokenError>(&text) {
+                Ok(err) => match err.error_description {
+                    Some(description) => format!("{} ({})", err.error, description),
+                    None => err.error,
+                },
+                Err(_) => format!("Unknown error ({}: {})", status, text),
+            };
+            // The token endpoint failing is not the same as the credentials
+            // being rejected, and may well pass if tried again.
+            let err = if status >= 500 {
+                Error::server(format!("Could not obtain access token: {}", message))
+            } else {
+                Error::unauthorized(format!("Could not obtain access token: {}", message))
+            };
+            return Err(err.with_response(Some(status), text));
+        }
+
+        #[derive(Deserialize)]
+        struct TokenResponse {
+            access_token: String,
+            refresh_token: String,
+            #[serde(default)]
+            scope: String,
+            expires_in: i64,
+        }
+
+        let token: TokenResponse = match serde_json::from_str(&text) {
+            Ok(r) => r,
+            Err(err) => {
+                return Err(Error::serialization(format!(
+                    "Could not deserialize token response from \"{}\" ({}).",
+                    text, err
+                ))
+                .with_source(err))
+            }
+        };
+
+        Ok(TokenSet {
+            access_token: token.access_token,
+            refresh_token: token.refresh_token,
+            scope: token.scope,
+            expires_at: Utc::now() + Duration::seconds(token.expires_in),
+        })
+    }
+}
+
+// How rotated tokens are handed back to the caller.
+pub(crate) enum Persistence {
+    Callback(Box<dyn Fn(&TokenSet) + Send + Sync>),
+    Store(Arc<dyn TokenStore>),
+}
+
+// The OAuth state shared by a gateway. The lock is held for the whole refresh so
+// that concurrent requests never spend the same (single-use) refresh token twice.
+pub(crate) struct Session {
+    client: OAuthClient,
+    http: reqwest::Client,
+    tokens: Mutex<TokenSet>,
+    persistence: Persistence,
+}
+
+impl Session {
+    pub(crate) fn new(
+        client: OAuthClient,
+        http: reqwest::Client,
+        tokens: TokenSet,
+        persistence: Persistence,
+    ) -> Session {
+        Session {
+            client,
+            http,
+            tokens: Mutex::new(tokens),
+            persistence,
+        }
+    }
+
+    pub(crate) async fn access_token(&self) -> Result<String, Error> {
+        let mut tokens = self.tokens.lock().await;
+        if tokens.is_expired() {
+            self.refresh(&mut tokens).await?;
+        }
+        Ok(tokens.access_token.clone())
+    }
+
+    // Called when Fortnox rejects an access token. Another request may already
+    // have replaced it, in which case there is nothing to do.
+    pub(crate) async fn invalidate(&self, rejected: &str) -> Result<(), Error> {
+        let mut tokens = self.tokens.lock().await;
+        if tokens.access_token == rejected {
+            // Should the refresh fail, the next `access_token` tries again
+            // rather than handing out the rejected token.
+            tokens.expires_at = Utc.timestamp_opt(0, 0).unwrap();
+            self.refresh(&mut tokens).await?;
+        }
+        Ok(())
+    }
+
+    async fn refresh(&self, tokens: &mut TokenSet) -> Result<(), Error> {
+        let store = match &self.persistence {
+            Persistence::Callback(on_refresh) => {
+                let refreshed = self
+                    .client
+                    .refresh(&self.http, &tokens.refresh_token)
+                    .await?;
+
+                // The previous refresh token is no longer valid, so hand the new
+                // one over before it is used for anything.
+                on_refresh(&refreshed);
+                *tokens = refreshed;
+                return Ok(());
+            }
+            Persistence::Store(store) => store,
+        };
+
+        // Another process sharing the store may already have rotated the tokens.
+        // Its access token may since have expired too, but its refresh token is
+        // the one that is still valid, so it is the one to refresh with.
+        let mut adopted = adopt_stored(store, tokens).await?;
+        loop {
+            if adopted && !tokens.is_expired() {
+                return Ok(());
+            }
+
+            let refreshed = match self.client.refresh(&self.http, &tokens.refresh_token).await {
+                Ok(refreshed) => refreshed,
+                Err(err) if err.kind() == ErrorKind::Unauthorized => {
+                    // Most likely someone else spent the refresh token after we
+                    // looked in the store. Give them a moment to save the new one.
+                    adopted = wait_for_stored(store, tokens).await?;
+                    if !adopted {
+                        return Err(err);
+                    }
+                    continue;
+                }
+                Err(err) => return Err(err),
+            };
+
+            // If the swap fails the store already holds tokens from a concurrent
+            // refresh. Ours are still valid, so keep using them without
+            // overwriting.
+            let current = tokens.refresh_token.clone();
+            let new = refreshed.clone();
+            blocking(store, move |store| store.compare_and_swap(&current, &new)).await?;
+            *tokens = refreshed;
+            return Ok(());
+        }
+    }
+}
+
+// Replaces `tokens` with the stored ones if they have been rotated since
+// `tokens` was loaded, whether or not their access token has expired.
+async fn adopt_stored(store: &Arc<dyn TokenStore>, tokens: &mut TokenSet) -> Result<bool, Error> {
+    match blocking(store, |store| store.load()).await? {
+        Some(stored) if stored.refresh_token != tokens.refresh_token => {
+            *tokens = stored;
+            Ok(true)
+        }
+        _ => Ok(false),
+    }
+}
+
+async fn wait_for_stored(
+    store: &Arc<dyn TokenStore>,
+    tokens: &mut TokenSet,
+) -> Result<bool, Error> {
+    for _ in 0..STORE_RETRIES {
+        sleep(StdDuration::from_millis(STORE_RETRY_DELAY_MS)).await;
+        if adopt_stored(store, tokens).await? {
+            return Ok(true);
+        }
+    }
+    Ok(false)
+}
+
+// Token stores may block, e.g. `FileTokenStore` while it waits for its lock
+// file, so they are called outside of the async runtime.
+pub(crate) async fn blocking<T: Send + 'static>(
+    store: &Arc<dyn TokenStore>,
+    f: impl FnOnce(&dyn TokenStore) -> Result<T, Error> + Send + 'static,
+) -> Result<T, Error> {
+    let store = store.clone();
+    match tokio::task::spawn_blocking(move || f(store.as_ref())).await {
+        Ok(res) => res,
+        Err(err) => {
+            Err(Error::unspecified(format!("Token store task failed ({}).", err)).with_source(err))
+        }
+    }
+}
@@ -0,0 +1,379 @@
+use crate::order::serialize_unknown;
+use crate::validation::Validator;
+use crate::{
+    lenient, Amount, Currency, EmailInformation, FieldError, Label, Language, Resource, Row,
+    ValidationMode,
+};
+use chrono::NaiveDate;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+// A quote sent to a customer, which can later be turned into an order or an
+// invoice.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "PascalCase")]
+pub struct Offer {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(default)]
+    #[serde(deserialize_with = "lenient::option_string")]
+    pub document_number: Option<String>,
+
+    #[serde(deserialize_with = "lenient::string")]
+    pub customer_number: String,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(default)]
+    #[serde(deserialize_with = "lenient::option_string")]
+    pub customer_name: Option<String>,
+
+    #[serde(default)]
+    pub offer_rows: Vec<Row>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(default)]
+    #[serde(deserialize_with = "lenient::option")]
+    pub currency: Option<Currency>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(rename = "VATIncluded")]
+    #[serde(default)]
+    #[serde(deserialize_with = "lenient::option_boolean")]
+    pub vat_included: Option<bool>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(default)]
+    #[serde(deserialize_with = "lenient::option")]
+    pub offer_date: Option<NaiveDate>, // "2006-01-02"
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(default)]
+    #[serde(deserialize_with = "lenient::option")]
+    pub expire_date: Option<NaiveDate>, // "2006-01-02"
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(default)]
+    #[serde(deserialize_with = "lenient::option")]
+    pub delivery_date: Option<NaiveDate>, // "2006-01-02"
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(default)]
+    #[serde(deserialize_with = "lenient::option_string")]
+    pub comments: Option<String>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(default)]
+    #[serde(deserialize_with = "lenient::option_string")]
+    pub remarks: Option<String>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(default)]
+    #[serde(deserialize_with = "lenient::option_string")]
+    pub address1: Option<String>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(default)]
+    #[serde(deserialize_with = "lenient::option_string")]
+    pub address2: Option<String>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(default)]
+    #[serde(deserialize_with = "lenient::option_string")]
+    pub zip_code: Option<String>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(default)]
+    #[serde(deserialize_with = "lenient::option_string")]
+    pub city: Option<String>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(default)]
+    #[serde(deserialize_with = "lenient::option_string")]
+    pub country: Option<String>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(default)]
+    #[serde(deserialize_with = "lenient::option_string")]
+    pub delivery_name: Option<String>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(default)]
+    #[serde(deserialize_with = "lenient::option_string")]
+    pub delivery_address1: Option<String>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(default)]
+    #[serde(deserialize_with = "lenient::option_string")]
+    pub delivery_address2: Option<String>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(default)]
+    #[serde(deserialize_with = "lenient::option_string")]
+    pub delivery_zip_code: Option<String>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(default)]
+    #[serde(deserialize_with = "lenient::option_string")]
+    pub delivery_city: Option<String>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(default)]
+    #[serde(deserialize_with = "lenient::option_string")]
+    pub delivery_country: Option<String>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(default)]
+    #[serde(deserialize_with = "lenient::option_string")]
+    pub our_reference: Option<String>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(default)]
+    #[serde(deserialize_with = "lenient::option_string")]
+    pub your_reference: Option<String>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(default)]
+    #[serde(deserialize_with = "lenient::option_string")]
+    pub your_reference_number: Option<String>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(default)]
+    #[serde(deserialize_with = "lenient::option_string")]
+    pub project: Option<String>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(default)]
+    #[serde(deserialize_with = "lenient::option_string")]
+    pub cost_center: Option<String>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(default)]
+    #[serde(deserialize_with = "lenient::option_string")]
+    pub price_list: Option<String>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(default)]
+    #[serde(deserialize_with = "lenient::option_string")]
+    pub terms_of_payment: Option<String>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(default)]
+    #[serde(deserialize_with = "lenient::option_string")]
+    pub terms_of_delivery: Option<String>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(default)]
+    #[serde(deserialize_with = "lenient::option_string")]
+    pub way_of_delivery: Option<String>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(default)]
+    #[serde(deserialize_with = "lenient::option")]
+    pub freight: Option<Amount>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(default)]
+    #[serde(deserialize_with = "lenient::option")]
+    pub administration_fee: Option<Amount>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(default)]
+    #[serde(deserialize_with = "lenient::option")]
+    pub language: Option<Language>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(default)]
+    pub email_information: Option<EmailInformation>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(default)]
+    pub labels: Option<Vec<Label>>,
+
+    #[serde(skip_serializing)]
+    #[serde(default)]
+    #[serde(deserialize_with = "lenient::option_string")]
+    pub order_reference: Option<String>, // Read-only.
+
+    #[serde(skip_serializing)]
+    #[serde(default)]
+    #[serde(deserialize_with = "lenient::option_string")]
+    pub invoice_reference: Option<String>, // Read-only.
+
+    #[serde(skip_serializing)]
+    #[serde(default)]
+    #[serde(deserialize_with = "lenient::boolean")]
+    pub cancelled: bool, // Read-only.
+
+    #[serde(skip_serializing)]
+    #[serde(default)]
+    #[serde(deserialize_with = "lenient::boolean")]
+    pub sent: bool, // Read-only.
+
+    #[serde(skip_serializing)]
+    #[serde(default)]
+    #[serde(deserialize_with = "lenient::option")]
+    pub total: Option<Amount>, // Read-only.
+
+    #[serde(skip_serializing)]
+    #[serde(rename = "TotalVAT")]
+    #[serde(default)]
+    #[serde(deserialize_with = "lenient::option")]
+    pub total_vat: Option<Amount>, // Read-only.
+
+    #[serde(skip_serializing)]
+    #[serde(default)]
+    #[serde(deserialize_with = "lenient::option")]
+    pub net: Option<Amount>, // Read-only.
+
+    // Fields that have no typed counterpart above, kept so that an offer that is
+    // fetched, changed and sent back with `edit_offer` does not lose them.
+    #[serde(flatten)]
+    #[serde(serialize_with = "serialize_unknown")]
+    pub unknown: HashMap<String, serde_json::Value>,
+}
+
+impl Offer {
+    // Fortnox fills in the currency, dates, terms and addresses from the
+    // customer for anything that is left as None. Rows take their `quantity`.
+    pub fn new(customer_number: String, offer_rows: Vec<Row>) -> Offer {
+        Offer {
+            document_number: None,
+            customer_number,
+            customer_name: None,
+            offer_rows,
+            currency: None,
+            vat_included: None,
+            offer_date: None,
+            expire_date: None,
+            delivery_date: None,
+            comments: None,
+            remarks: None,
+            address1: None,
+            address2: None,
+            zip_code: None,
+            city: None,
+            country: None,
+            delivery_name: None,
+            delivery_address1: None,
+            delivery_address2: None,
+            delivery_zip_code: None,
+            delivery_city: None,
+            delivery_country: None,
+            our_reference: None,
+            your_reference: None,
+            your_reference_number: None,
+            project: None,
+            cost_center: None,
+            price_list: None,
+            terms_of_payment: None,
+            terms_of_delivery: None,
+            way_of_delivery: None,
+            freight: None,
+            administration_fee: None,
+            language: None,
+            email_information: None,
+            labels: None,
+            order_reference: None,
+            invoice_reference: None,
+            cancelled: false,
+            sent: false,
+            total: None,
+            total_vat: None,
+            net: None,
+            unknown: HashMap::new(),
+        }
+    }
+
+    // An offer needs a customer; an update also needs its document number.
+    pub fn validate(&self, mode: ValidationMode) -> Result<(), Vec<FieldError>> {
+        let mut v = Validator::new(mode);
+        if v.mode() == ValidationMode::Update {
+            v.required("DocumentNumber", self.document_number.as_deref());
+        }
+        v.numeric("DocumentNumber", self.document_number.as_deref(), 20);
+        v.required("CustomerNumber", Some(&self.customer_number));
+        v.identifier("CustomerNumber", Some(&self.customer_number), 1024);
+        v.text("CustomerName", self.customer_name.as_deref(), 1024);
+        v.text("Comments", self.comments.as_deref(), 1024);
+        v.text("Remarks", self.remarks.as_deref(), 1024);
+        v.text("Address1", self.address1.as_deref(), 1024);
+        v.text("Address2", self.address2.as_deref(), 1024);
+        v.text("ZipCode", self.zip_code.as_deref(), 1024);
+        v.text("City", self.city.as_deref(), 1024);
+        v.text("Country", self.country.as_deref(), 1024);
+        v.text("DeliveryName", self.delivery_name.as_deref(), 1024);
+        v.text("DeliveryAddress1", self.delivery_address1.as_deref(), 1024);
+        v.text("DeliveryAddress2", self.delivery_address2.as_deref(), 1024);
+        v.text("DeliveryZipCode", self.delivery_zip_code.as_deref(), 1024);
+        v.text("DeliveryCity", self.delivery_city.as_deref(), 1024);
+        v.text("DeliveryCountry", self.delivery_country.as_deref(), 1024);
+        v.text("OurReference", self.our_reference.as_deref(), 50);
+        v.text("YourReference", self.your_reference.as_deref(), 50);
+        v.text(
+            "YourReferenceNumber",
+            self.your_reference_number.as_deref(),
+            30,
+        );
+        v.email_information(self.email_information.as_ref());
+        v.rows("OfferRows", &self.offer_rows);
+        v.finish()
+    }
+}
+
+impl Resource for Offer {
+    const PATH: &'static str = "offers";
+    const KEY: &'static str = "Offer";
+    const LIST_KEY: &'static str = "Offers";
+    const ID_FIELD: &'static str = "DocumentNumber";
+    type ListItem = ListItem;
+
+    fn id(&self) -> Option<&str> {
+        self.document_number.as_deref()
+    }
+}
+
+// An offer as it appears in a list, which only carries the most common fields.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "PascalCase")]
+pub struct ListItem {
+    #[serde(deserialize_with = "lenient::string")]
+    pub document_number: String,
+
+    #[serde(deserialize_with = "lenient::string")]
+    pub customer_number: String,
+
+    #[serde(default)]
+    #[serde(deserialize_with = "lenient::option_string")]
+    pub customer_name: Option<String>,
+
+    #[serde(default)]
+    #[serde(deserialize_with = "lenient::option")]
+    pub currency: Option<Currency>,
+
+    #[serde(default)]
+    #[serde(deserialize_with = "lenient::option")]
+    pub offer_date: Option<NaiveDate>,
+
+    #[serde(default)]
+    #[serde(deserialize_with = "lenient::option")]
+    pub expire_date: Option<NaiveDate>,
+
+    #[serde(default)]
+    #[serde(deserialize_with = "lenient::option")]
+    pub total: Option<Amount>,
+
+    #[serde(default)]
+    #[serde(deserialize_with = "lenient::boolean")]
+    pub cancelled: bool,
+
+    #[serde(default)]
+    #[serde(deserialize_with = "lenient::boolean")]
+    pub sent: bool,
+
+    #[serde(default)]
+    #[serde(deserialize_with = "lenient::option_string")]
+    pub order_reference: Option<String>,
+
+    #[serde(default)]
+    #[serde(deserialize_with = "lenient::option_string")]
+    pub your_reference: Option<String>,
+}
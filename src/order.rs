@@ -1,14 +1,21 @@
-use crate::Currency;
+use crate::validation::Validator;
+use crate::{
+    lenient, Amount, Currency, EmailInformation, FieldError, Label, Language, Resource, Row,
+    ValidationMode,
+};
 use chrono::NaiveDate;
-use serde::{Deserialize, Serialize};
+use serde::{Deserialize, Serialize, Serializer};
+use std::collections::HashMap;
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
 #[serde(rename_all = "PascalCase")]
 pub struct Order {
     #[serde(skip_serializing_if = "Option::is_none")]
     #[serde(default)]
+    #[serde(deserialize_with = "lenient::option_string")]
     pub document_number: Option<String>,
 
+    #[serde(deserialize_with = "lenient::string")]
     pub customer_number: String,
 
     pub order_rows: Vec<Row>,
@@ -16,29 +23,291 @@ pub struct Order {
     pub currency: Currency,
 
     #[serde(rename = "VATIncluded")]
+    #[serde(deserialize_with = "lenient::boolean")]
     pub vat_included: bool,
 
     #[serde(skip_serializing_if = "Option::is_none")]
     #[serde(default)]
+    #[serde(deserialize_with = "lenient::option_string")]
     pub comments: Option<String>,
 
     pub delivery_date: NaiveDate, // "2006-01-02"
 
     pub order_date: NaiveDate, // "2006-01-02"
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(default)]
+    #[serde(deserialize_with = "lenient::option_string")]
+    pub delivery_name: Option<String>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(default)]
+    #[serde(deserialize_with = "lenient::option_string")]
+    pub delivery_address1: Option<String>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(default)]
+    #[serde(deserialize_with = "lenient::option_string")]
+    pub delivery_address2: Option<String>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(default)]
+    #[serde(deserialize_with = "lenient::option_string")]
+    pub delivery_zip_code: Option<String>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(default)]
+    #[serde(deserialize_with = "lenient::option_string")]
+    pub delivery_city: Option<String>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(default)]
+    #[serde(deserialize_with = "lenient::option_string")]
+    pub delivery_country: Option<String>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(default)]
+    #[serde(deserialize_with = "lenient::option_string")]
+    pub our_reference: Option<String>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(default)]
+    #[serde(deserialize_with = "lenient::option_string")]
+    pub your_reference: Option<String>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(default)]
+    #[serde(deserialize_with = "lenient::option_string")]
+    pub your_order_number: Option<String>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(default)]
+    #[serde(deserialize_with = "lenient::option_string")]
+    pub project: Option<String>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(default)]
+    #[serde(deserialize_with = "lenient::option_string")]
+    pub cost_center: Option<String>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(default)]
+    #[serde(deserialize_with = "lenient::option_string")]
+    pub price_list: Option<String>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(default)]
+    #[serde(deserialize_with = "lenient::option_string")]
+    pub terms_of_payment: Option<String>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(default)]
+    #[serde(deserialize_with = "lenient::option_string")]
+    pub terms_of_delivery: Option<String>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(default)]
+    #[serde(deserialize_with = "lenient::option_string")]
+    pub way_of_delivery: Option<String>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(default)]
+    #[serde(deserialize_with = "lenient::option")]
+    pub freight: Option<Amount>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(default)]
+    #[serde(deserialize_with = "lenient::option")]
+    pub administration_fee: Option<Amount>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(default)]
+    #[serde(deserialize_with = "lenient::option_string")]
+    pub external_invoice_reference1: Option<String>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(default)]
+    #[serde(deserialize_with = "lenient::option_string")]
+    pub external_invoice_reference2: Option<String>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(default)]
+    #[serde(deserialize_with = "lenient::option")]
+    pub language: Option<Language>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(default)]
+    pub email_information: Option<EmailInformation>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(default)]
+    pub labels: Option<Vec<Label>>,
+
+    #[serde(skip_serializing)]
+    #[serde(default)]
+    #[serde(deserialize_with = "lenient::option")]
+    pub total: Option<Amount>, // Read-only.
+
+    #[serde(skip_serializing)]
+    #[serde(rename = "TotalVAT")]
+    #[serde(default)]
+    #[serde(deserialize_with = "lenient::option")]
+    pub total_vat: Option<Amount>, // Read-only.
+
+    #[serde(skip_serializing)]
+    #[serde(default)]
+    #[serde(deserialize_with = "lenient::option")]
+    pub net: Option<Amount>, // Read-only.
+
+    // Fields that have no typed counterpart above, kept so that an order that is
+    // fetched, changed and sent back with `edit_order` does not lose them.
+    #[serde(flatten)]
+    #[serde(serialize_with = "serialize_unknown")]
+    pub unknown: HashMap<String, serde_json::Value>,
+}
+
+impl Order {
+    pub fn new(
+        customer_number: String,
+        order_rows: Vec<Row>,
+        currency: Currency,
+        vat_included: bool,
+        delivery_date: NaiveDate,
+        order_date: NaiveDate,
+    ) -> Order {
+        Order {
+            document_number: None,
+            customer_number,
+            order_rows,
+            currency,
+            vat_included,
+            comments: None,
+            delivery_date,
+            order_date,
+            delivery_name: None,
+            delivery_address1: None,
+            delivery_address2: None,
+            delivery_zip_code: None,
+            delivery_city: None,
+            delivery_country: None,
+            our_reference: None,
+            your_reference: None,
+            your_order_number: None,
+            project: None,
+            cost_center: None,
+            price_list: None,
+            terms_of_payment: None,
+            terms_of_delivery: None,
+            way_of_delivery: None,
+            freight: None,
+            administration_fee: None,
+            external_invoice_reference1: None,
+            external_invoice_reference2: None,
+            language: None,
+            email_information: None,
+            labels: None,
+            total: None,
+            total_vat: None,
+            net: None,
+            unknown: HashMap::new(),
+        }
+    }
+
+    // An order needs a customer; an update also needs its document number.
+    pub fn validate(&self, mode: ValidationMode) -> Result<(), Vec<FieldError>> {
+        let mut v = Validator::new(mode);
+        if v.mode() == ValidationMode::Update {
+            v.required("DocumentNumber", self.document_number.as_deref());
+        }
+        v.numeric("DocumentNumber", self.document_number.as_deref(), 20);
+        v.required("CustomerNumber", Some(&self.customer_number));
+        v.identifier("CustomerNumber", Some(&self.customer_number), 1024);
+        v.text("Comments", self.comments.as_deref(), 1024);
+        v.text("DeliveryName", self.delivery_name.as_deref(), 1024);
+        v.text("DeliveryAddress1", self.delivery_address1.as_deref(), 1024);
+        v.text("DeliveryAddress2", self.delivery_address2.as_deref(), 1024);
+        v.text("DeliveryZipCode", self.delivery_zip_code.as_deref(), 1024);
+        v.text("DeliveryCity", self.delivery_city.as_deref(), 1024);
+        v.text("DeliveryCountry", self.delivery_country.as_deref(), 1024);
+        v.text("OurReference", self.our_reference.as_deref(), 50);
+        v.text("YourReference", self.your_reference.as_deref(), 50);
+        v.text("YourOrderNumber", self.your_order_number.as_deref(), 30);
+        v.text(
+            "ExternalInvoiceReference1",
+            self.external_invoice_reference1.as_deref(),
+            80,
+        );
+        v.text(
+            "ExternalInvoiceReference2",
+            self.external_invoice_reference2.as_deref(),
+            80,
+        );
+        v.email_information(self.email_information.as_ref());
+        v.rows("OrderRows", &self.order_rows);
+        v.finish()
+    }
+}
+
+impl Resource for Order {
+    const PATH: &'static str = "orders";
+    const KEY: &'static str = "Order";
+    const LIST_KEY: &'static str = "Orders";
+    const ID_FIELD: &'static str = "DocumentNumber";
+    type ListItem = ListItem;
+
+    fn id(&self) -> Option<&str> {
+        self.document_number.as_deref()
+    }
 }
 
+// An order as it appears in a list, which only carries the most common fields.
 #[derive(Serialize, Deserialize, Debug, Clone)]
 #[serde(rename_all = "PascalCase")]
-pub struct Row {
-    #[serde(skip_serializing_if = "Option::is_none")]
+pub struct ListItem {
+    #[serde(deserialize_with = "lenient::string")]
+    pub document_number: String,
+
+    #[serde(deserialize_with = "lenient::string")]
+    pub customer_number: String,
+
     #[serde(default)]
-    pub article_number: Option<String>,
+    #[serde(deserialize_with = "lenient::option_string")]
+    pub customer_name: Option<String>,
 
-    pub ordered_quantity: String,
+    #[serde(default)]
+    #[serde(deserialize_with = "lenient::option")]
+    pub currency: Option<Currency>,
 
-    pub delivered_quantity: String,
+    #[serde(default)]
+    #[serde(deserialize_with = "lenient::option")]
+    pub order_date: Option<NaiveDate>,
+
+    #[serde(default)]
+    #[serde(deserialize_with = "lenient::option")]
+    pub delivery_date: Option<NaiveDate>,
 
-    pub description: String,
+    #[serde(default)]
+    #[serde(deserialize_with = "lenient::option")]
+    pub total: Option<Amount>,
+
+    #[serde(default)]
+    #[serde(deserialize_with = "lenient::boolean")]
+    pub cancelled: bool,
+
+    #[serde(default)]
+    #[serde(deserialize_with = "lenient::boolean")]
+    pub sent: bool,
+
+    #[serde(default)]
+    #[serde(deserialize_with = "lenient::option_string")]
+    pub your_reference: Option<String>,
+}
 
-    pub price: f64,
+// Metadata such as `@url` is read-only and refused by Fortnox, so it is not sent
+// back along with the other unknown fields.
+pub(crate) fn serialize_unknown<S: Serializer>(
+    unknown: &HashMap<String, serde_json::Value>,
+    serializer: S,
+) -> Result<S::Ok, S::Error> {
+    serializer.collect_map(unknown.iter().filter(|(key, _)| !key.starts_with('@')))
 }
@@ -0,0 +1,83 @@
+use crate::{Error, Gateway, ListQuery};
+use futures::stream::{self, Stream, TryStreamExt};
+use serde::de::DeserializeOwned;
+use serde::Deserialize;
+use std::collections::HashMap;
+
+#[derive(Deserialize, Debug, Clone)]
+pub(crate) struct MetaInformation {
+    #[serde(rename = "@TotalPages")]
+    pub total_pages: u32,
+
+    #[serde(rename = "@CurrentPage")]
+    pub current_page: u32,
+}
+
+impl Gateway {
+    // Walks every page of a list resource, e.g. `customers`, yielding the items
+    // found under `key` (`Customers`) in each response.
+    pub(crate) fn paginate<'a, R, T: DeserializeOwned + 'a>(
+        &'a self,
+        path: &'a str,
+        key: &'static str,
+        query: &ListQuery<R>,
+    ) -> impl Stream<Item = Result<T, Error>> + 'a {
+        let query = query.to_query();
+        stream::try_unfold(Some(1), move |page| {
+            let query = query.clone();
+            async move {
+                let page: u32 = match page {
+                    Some(page) => page,
+                    None => return Ok(None),
+                };
+
+                #[derive(Deserialize)]
+                struct Response {
+                    #[serde(rename = "MetaInformation")]
+                    meta_information: MetaInformation,
+                    #[serde(flatten)]
+                    resources: HashMap<String, serde_json::Value>,
+                }
+
+                let mut params = query?;
+                params.push(("page", page.to_string()));
+                let url = match reqwest::Url::parse_with_params(
+                    &format!("{}/{}", self.base_url, path),
+                    &params,
+                ) {
+                    Ok(url) => url,
+                    Err(err) => {
+                        return Err(Error::parse(format!(
+                            "Could not build URL for {} ({}).",
+                            path, err
+                        ))
+                        .with_source(err))
+                    }
+                };
+                let mut res: Response = self.http_get(url.as_str()).await?;
+
+                let items: Vec<T> = match res.resources.remove(key) {
+                    Some(items) => match serde_json::from_value(items) {
+                        Ok(items) => items,
+                        Err(err) => {
+                            return Err(Error::serialization(format!(
+                                "Could not deserialize {} on page {} ({}).",
+                                key, page, err
+                            ))
+                            .with_source(err))
+                        }
+                    },
+                    None => vec![],
+                };
+
+                let next = if res.meta_information.current_page < res.meta_information.total_pages {
+                    Some(page + 1)
+                } else {
+                    None
+                };
+                Ok(Some((stream::iter(items.into_iter().map(Ok)), next)))
+            }
+        })
+        .try_flatten()
+    }
+}
@@ -0,0 +1,9 @@
+use crate::{Download, Error, Gateway, PDF};
+
+impl Gateway {
+    // The invoice as a PDF, without marking it as sent.
+    pub async fn preview_invoice(&self, document_number: &str) -> Result<Download, Error> {
+        let url = self.action_url("invoices", document_number, "preview")?;
+        self.get_bytes(&url, PDF).await
+    }
+}
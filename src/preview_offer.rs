@@ -0,0 +1,9 @@
+use crate::{Download, Error, Gateway, PDF};
+
+impl Gateway {
+    // The offer as a PDF, without marking it as sent.
+    pub async fn preview_offer(&self, document_number: &str) -> Result<Download, Error> {
+        let url = self.action_url("offers", document_number, "preview")?;
+        self.get_bytes(&url, PDF).await
+    }
+}
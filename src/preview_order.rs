@@ -0,0 +1,9 @@
+use crate::{Download, Error, Gateway, PDF};
+
+impl Gateway {
+    // The order confirmation as a PDF, without marking the order as sent.
+    pub async fn preview_order(&self, document_number: &str) -> Result<Download, Error> {
+        let url = self.action_url("orders", document_number, "preview")?;
+        self.get_bytes(&url, PDF).await
+    }
+}
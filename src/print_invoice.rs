@@ -0,0 +1,9 @@
+use crate::{Download, Error, Gateway, PDF};
+
+impl Gateway {
+    // The invoice as a PDF. Fortnox marks the invoice as sent.
+    pub async fn print_invoice(&self, document_number: &str) -> Result<Download, Error> {
+        let url = self.action_url("invoices", document_number, "print")?;
+        self.get_bytes(&url, PDF).await
+    }
+}
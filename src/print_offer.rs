@@ -0,0 +1,9 @@
+use crate::{Download, Error, Gateway, PDF};
+
+impl Gateway {
+    // The offer as a PDF. Fortnox marks the offer as sent.
+    pub async fn print_offer(&self, document_number: &str) -> Result<Download, Error> {
+        let url = self.action_url("offers", document_number, "print")?;
+        self.get_bytes(&url, PDF).await
+    }
+}
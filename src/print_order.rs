@@ -0,0 +1,9 @@
+use crate::{Download, Error, Gateway, PDF};
+
+impl Gateway {
+    // The order confirmation as a PDF. Fortnox marks the order as sent.
+    pub async fn print_order(&self, document_number: &str) -> Result<Download, Error> {
+        let url = self.action_url("orders", document_number, "print")?;
+        self.get_bytes(&url, PDF).await
+    }
+}
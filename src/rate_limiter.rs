@@ -0,0 +1,137 @@
+use std::collections::VecDeque;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tokio::time::{sleep, Instant};
+
+// Fortnox allows 25 requests per 5 seconds per tenant.
+const REQUESTS: u32 = 25;
+const PERIOD_SECS: u64 = 5;
+
+// A sliding window that lets at most `capacity` requests through in any
+// `period`. Clones share the same window, so one limiter can be given to every
+// gateway that talks to the same tenant.
+#[derive(Debug, Clone)]
+pub struct RateLimiter {
+    capacity: usize,
+    period: Duration,
+    // When the most recent requests were let through, oldest first.
+    sent: Arc<Mutex<VecDeque<Instant>>>,
+}
+
+impl RateLimiter {
+    pub fn new(capacity: u32, period: Duration) -> RateLimiter {
+        let capacity = capacity.max(1) as usize;
+        RateLimiter {
+            capacity,
+            period,
+            sent: Arc::new(Mutex::new(VecDeque::with_capacity(capacity))),
+        }
+    }
+
+    // Waits until a request may be sent and records it.
+    pub async fn acquire(&self) {
+        loop {
+            let wait = {
+                let mut sent = match self.sent.lock() {
+                    Ok(sent) => sent,
+                    // The window is always left in a consistent state.
+                    Err(poisoned) => poisoned.into_inner(),
+                };
+
+                let now = Instant::now();
+                while let Some(&oldest) = sent.front() {
+                    if now.duration_since(oldest) >= self.period {
+                        sent.pop_front();
+                    } else {
+                        break;
+                    }
+                }
+
+                match sent.front() {
+                    Some(&oldest) if sent.len() >= self.capacity => {
+                        self.period - now.duration_since(oldest)
+                    }
+                    _ => {
+                        sent.push_back(now);
+                        return;
+                    }
+                }
+            };
+
+            sleep(wait).await;
+        }
+    }
+}
+
+impl Default for RateLimiter {
+    fn default() -> RateLimiter {
+        RateLimiter::new(REQUESTS, Duration::from_secs(PERIOD_SECS))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use tokio::time::timeout;
+
+    // How many `acquire` calls return within `within`, with `tasks` of them
+    // trying at once.
+    async fn acquired(limiter: &RateLimiter, tasks: usize, within: Duration) -> usize {
+        let count = Arc::new(AtomicUsize::new(0));
+        let handles: Vec<_> = (0..tasks)
+            .map(|_| {
+                let limiter = limiter.clone();
+                let count = count.clone();
+                tokio::spawn(async move {
+                    limiter.acquire().await;
+                    count.fetch_add(1, Ordering::SeqCst);
+                })
+            })
+            .collect();
+
+        sleep(within).await;
+        for handle in handles {
+            handle.abort();
+        }
+        count.load(Ordering::SeqCst)
+    }
+
+    #[tokio::test]
+    async fn lets_capacity_through_per_period() {
+        let period = Duration::from_millis(500);
+        let limiter = RateLimiter::new(5, period);
+        assert_eq!(acquired(&limiter, 20, period / 2).await, 5);
+
+        // Nothing more until the first ones are a whole period old.
+        assert_eq!(acquired(&limiter, 20, period / 5).await, 0);
+    }
+
+    #[tokio::test]
+    async fn never_exceeds_capacity_in_any_period() {
+        let period = Duration::from_millis(400);
+        let limiter = RateLimiter::new(5, period);
+        let started = Instant::now();
+        let mut sent = vec![];
+        for _ in 0..12 {
+            limiter.acquire().await;
+            sent.push(started.elapsed());
+        }
+
+        for (i, at) in sent.iter().enumerate().skip(5) {
+            assert!(*at - sent[i - 5] >= period);
+        }
+        assert!(sent[11] >= period * 2);
+    }
+
+    #[tokio::test]
+    async fn clones_share_the_window() {
+        let period = Duration::from_millis(500);
+        let limiter = RateLimiter::new(3, period);
+        let clone = limiter.clone();
+        for _ in 0..3 {
+            limiter.acquire().await;
+        }
+        assert!(timeout(period / 2, clone.acquire()).await.is_err());
+    }
+}
@@ -0,0 +1,159 @@
+use crate::{Error, Gateway, ListQuery};
+use futures::stream::TryStreamExt;
+use reqwest::Url;
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use serde_json::{Map, Value};
+
+// A Fortnox resource that can be fetched, created, updated, deleted and listed
+// with the generic `Gateway` methods. Implement it for your own type to reach an
+// endpoint that has no methods of its own, e.g.
+//
+//     impl Resource for Project {
+//         const PATH: &'static str = "projects";
+//         const KEY: &'static str = "Project";
+//         const LIST_KEY: &'static str = "Projects";
+//         const ID_FIELD: &'static str = "ProjectNumber";
+//         type ListItem = Project;
+//
+//         fn id(&self) -> Option<&str> {
+//             self.project_number.as_deref()
+//         }
+//     }
+pub trait Resource: Serialize + DeserializeOwned {
+    // The collection path below the base URL, e.g. `customers`.
+    const PATH: &'static str;
+
+    // The key that wraps a single record in requests and responses, e.g.
+    // `Customer`.
+    const KEY: &'static str;
+
+    // The key that wraps the records of a list response, e.g. `Customers`.
+    const LIST_KEY: &'static str;
+
+    // The field that identifies a record, e.g. `CustomerNumber`.
+    const ID_FIELD: &'static str;
+
+    // What a record looks like in a list, which is often a subset of it.
+    type ListItem: DeserializeOwned;
+
+    // The value of `ID_FIELD`, which is None until the record has been created.
+    fn id(&self) -> Option<&str>;
+}
+
+impl Gateway {
+    pub async fn get<T: Resource>(&self, id: &str) -> Result<T, Error> {
+        let url = self.record_url(T::PATH, id)?;
+        let res: Map<String, Value> = self.http_get(&url).await?;
+        unwrap::<T>(res)
+    }
+
+    pub async fn create<T: Resource>(&self, resource: &T) -> Result<T, Error> {
+        let url = format!("{}/{}", self.base_url, T::PATH);
+        let res: Map<String, Value> = self.http_post(&url, &wrap(resource)?).await?;
+        unwrap::<T>(res)
+    }
+
+    pub async fn update<T: Resource>(&self, resource: &T) -> Result<T, Error> {
+        let id = match resource.id() {
+            Some(id) => id,
+            None => {
+                return Err(Error::serialization(format!(
+                    "Cannot update {} with no {}.",
+                    T::KEY,
+                    T::ID_FIELD
+                )))
+            }
+        };
+
+        let url = self.record_url(T::PATH, id)?;
+        let res: Map<String, Value> = self.http_put(&url, &wrap(resource)?).await?;
+        unwrap::<T>(res)
+    }
+
+    pub async fn delete<T: Resource>(&self, id: &str) -> Result<(), Error> {
+        let url = self.record_url(T::PATH, id)?;
+        self.http_delete(&url).await
+    }
+
+    // The URL of the record `id` below `path`. The id is percent-encoded, since
+    // e.g. an article number may contain a slash.
+    pub(crate) fn record_url(&self, path: &str, id: &str) -> Result<String, Error> {
+        self.url(&[path, id])
+    }
+
+    // The URL of an action on the record `id` below `path`, e.g.
+    // `orders/1/cancel`, with the id encoded as in `record_url`.
+    pub(crate) fn action_url(&self, path: &str, id: &str, action: &str) -> Result<String, Error> {
+        self.url(&[path, id, action])
+    }
+
+    fn url(&self, segments: &[&str]) -> Result<String, Error> {
+        let mut url = match Url::parse(&self.base_url) {
+            Ok(url) => url,
+            Err(err) => {
+                return Err(Error::unspecified(format!(
+                    "Invalid base URL {} ({}).",
+                    self.base_url, err
+                ))
+                .with_source(err))
+            }
+        };
+        match url.path_segments_mut() {
+            Ok(mut path) => {
+                path.pop_if_empty().extend(segments);
+            }
+            Err(()) => {
+                return Err(Error::unspecified(format!(
+                    "Invalid base URL {}.",
+                    self.base_url
+                )))
+            }
+        }
+        Ok(url.into())
+    }
+
+    // Walks every page, see `ListQuery`.
+    pub async fn list<T: Resource>(&self, query: &ListQuery<T>) -> Result<Vec<T::ListItem>, Error> {
+        self.paginate(T::PATH, T::LIST_KEY, query)
+            .try_collect()
+            .await
+    }
+}
+
+fn wrap<T: Resource>(resource: &T) -> Result<Map<String, Value>, Error> {
+    let value = match serde_json::to_value(resource) {
+        Ok(value) => value,
+        Err(err) => {
+            return Err(
+                Error::serialization(format!("Could not serialize {} ({}).", T::KEY, err))
+                    .with_source(err),
+            )
+        }
+    };
+    let mut body = Map::new();
+    body.insert(T::KEY.to_string(), value);
+    Ok(body)
+}
+
+fn unwrap<T: Resource>(mut res: Map<String, Value>) -> Result<T, Error> {
+    let value = match res.remove(T::KEY) {
+        Some(value) => value,
+        None => {
+            return Err(Error::serialization(format!(
+                "Response has no {} ({}).",
+                T::KEY,
+                Value::Object(res)
+            )))
+        }
+    };
+    match serde_json::from_value(value) {
+        Ok(resource) => Ok(resource),
+        Err(err) => {
+            Err(
+                Error::serialization(format!("Could not deserialize {} ({}).", T::KEY, err))
+                    .with_source(err),
+            )
+        }
+    }
+}
@@ -0,0 +1,142 @@
+use crate::{Error, ErrorKind};
+use rand::Rng;
+use reqwest::Method;
+use std::fmt;
+use std::sync::Arc;
+use std::time::Duration;
+
+const INITIAL_DELAY_MS: u64 = 100;
+const MAX_DELAY_MS: u64 = 10_000;
+const RETRIES: u8 = 5;
+const BACKOFF: f64 = 2.0;
+const JITTER: f64 = 0.25;
+
+type Retryable = Arc<dyn Fn(&Error) -> bool + Send + Sync>;
+
+// Randomized exponential backoff (cf.
+// https://cloud.google.com/appengine/articles/scalability#backoff ).
+#[derive(Clone)]
+pub struct RetryPolicy {
+    // The total number of attempts, including the first one.
+    pub max_attempts: u8,
+
+    // The delay before the first retry.
+    pub initial_delay: Duration,
+
+    // The factor the delay grows by after each retry.
+    pub backoff: f64,
+
+    // The longest delay between two attempts, before jitter.
+    pub max_delay: Duration,
+
+    // How much of the delay is randomized, to avoid a thundering herd when many
+    // clients back off at once. 0.25 spreads the delay between 75% and 125%.
+    pub jitter: f64,
+
+    // Whether to wait as long as a Retry-After header asks, up to `max_delay`,
+    // instead of the computed delay, when the response has one.
+    pub respect_retry_after: bool,
+
+    retryable: Option<Retryable>,
+}
+
+impl RetryPolicy {
+    // A policy that gives up after the first attempt.
+    pub fn none() -> RetryPolicy {
+        RetryPolicy {
+            max_attempts: 1,
+            ..RetryPolicy::default()
+        }
+    }
+
+    // Replaces the decision of which errors are worth retrying. Defaults to
+    // `RetryPolicy::is_transient`.
+    pub fn retry_if(
+        mut self,
+        retryable: impl Fn(&Error) -> bool + Send + Sync + 'static,
+    ) -> RetryPolicy {
+        self.retryable = Some(Arc::new(retryable));
+        self
+    }
+
+    // Throttling, server errors and failed connections.
+    pub fn is_transient(error: &Error) -> bool {
+        error.is_retryable()
+    }
+
+    // Whether to send the request again after `error`. Only GET, HEAD and
+    // DELETE are safe to repeat once they may have reached Fortnox; anything
+    // else, and a GET that e.g. sends an e-mail, might be carried out twice, so
+    // it is only retried when Fortnox throttled it or the connection was never
+    // made. The rest is up to `retry_if`.
+    pub(crate) fn is_retryable(&self, method: &Method, idempotent: bool, error: &Error) -> bool {
+        let idempotent =
+            idempotent && matches!(*method, Method::GET | Method::HEAD | Method::DELETE);
+        if !idempotent && error.kind() != ErrorKind::Throttling && !error.is_connect() {
+            return false;
+        }
+        self.is_retryable_unsent(error)
+    }
+
+    // Whether to try again after failing before the request was sent, e.g. to
+    // get an access token. Nothing can have been carried out twice, so it is
+    // only a matter of whether the error is transient.
+    pub(crate) fn is_retryable_unsent(&self, error: &Error) -> bool {
+        match &self.retryable {
+            Some(retryable) => retryable(error),
+            None => RetryPolicy::is_transient(error),
+        }
+    }
+
+    // The delay before retry number `retry` (starting at 1).
+    pub(crate) fn delay(&self, retry: u32, retry_after: Option<Duration>) -> Duration {
+        // Capped like any other delay, so that a server asking for an hour does
+        // not stall the call for that long.
+        if let (true, Some(retry_after)) = (self.respect_retry_after, retry_after) {
+            return retry_after.min(self.max_delay);
+        }
+
+        let exponent = retry.saturating_sub(1).min(i32::MAX as u32) as i32;
+        let delay = (self.initial_delay.as_secs_f64() * self.backoff.powi(exponent))
+            .min(self.max_delay.as_secs_f64());
+
+        // Values taken from https://github.com/GoogleCloudPlatform/appengine-gcs-client/
+        // blob/master/java/src/main/java/com/google/appengine/tools/cloudstorage/RetryHelper.java .
+        let jitter = self.jitter.clamp(0.0, 1.0);
+        let factor = if jitter > 0.0 {
+            rand::thread_rng().gen_range(1.0 - jitter..=1.0 + jitter)
+        } else {
+            1.0
+        };
+
+        Duration::from_secs_f64((delay * factor).max(0.0))
+    }
+}
+
+impl Default for RetryPolicy {
+    fn default() -> RetryPolicy {
+        RetryPolicy {
+            max_attempts: RETRIES,
+            initial_delay: Duration::from_millis(INITIAL_DELAY_MS),
+            backoff: BACKOFF,
+            max_delay: Duration::from_millis(MAX_DELAY_MS),
+            jitter: JITTER,
+            respect_retry_after: true,
+            retryable: None,
+        }
+    }
+}
+
+impl fmt::Debug for RetryPolicy {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("RetryPolicy")
+            .field("max_attempts", &self.max_attempts)
+            .field("initial_delay", &self.initial_delay)
+            .field("backoff", &self.backoff)
+            .field("max_delay", &self.max_delay)
+            .field("jitter", &self.jitter)
+            .field("respect_retry_after", &self.respect_retry_after)
+            .field("retryable", &self.retryable.as_ref().map(|_| "custom"))
+            .finish()
+    }
+}
@@ -0,0 +1,93 @@
+use crate::order::serialize_unknown;
+use crate::{lenient, Amount, Quantity};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+// A row of an order, offer or invoice. They share everything but the quantity:
+// offers have a `quantity`, invoices a `delivered_quantity` and orders both an
+// `ordered_quantity` and a `delivered_quantity`. Quantities that do not apply
+// to the document are left as None.
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+#[serde(rename_all = "PascalCase")]
+pub struct Row {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(default)]
+    #[serde(deserialize_with = "lenient::option_string")]
+    pub article_number: Option<String>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(default)]
+    #[serde(deserialize_with = "lenient::option")]
+    pub ordered_quantity: Option<Quantity>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(default)]
+    #[serde(deserialize_with = "lenient::option")]
+    pub delivered_quantity: Option<Quantity>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(default)]
+    #[serde(deserialize_with = "lenient::option")]
+    pub quantity: Option<Quantity>,
+
+    // Taken from the article when left as None.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(default)]
+    #[serde(deserialize_with = "lenient::option_string")]
+    pub description: Option<String>,
+
+    // Taken from the article and price list when left as None.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(default)]
+    #[serde(deserialize_with = "lenient::option")]
+    pub price: Option<Amount>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(default)]
+    #[serde(deserialize_with = "lenient::option")]
+    pub discount: Option<Amount>,
+
+    // "AMOUNT" or "PERCENT".
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(default)]
+    #[serde(deserialize_with = "lenient::option_string")]
+    pub discount_type: Option<String>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(default)]
+    #[serde(deserialize_with = "lenient::option_string")]
+    pub unit: Option<String>,
+
+    // In percent, e.g. 25.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(rename = "VAT")]
+    #[serde(default)]
+    #[serde(deserialize_with = "lenient::option")]
+    pub vat: Option<u32>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(default)]
+    #[serde(deserialize_with = "lenient::option")]
+    pub account_number: Option<u32>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(default)]
+    #[serde(deserialize_with = "lenient::option_string")]
+    pub cost_center: Option<String>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(default)]
+    #[serde(deserialize_with = "lenient::option_string")]
+    pub project: Option<String>,
+
+    #[serde(skip_serializing)]
+    #[serde(default)]
+    #[serde(deserialize_with = "lenient::option")]
+    pub total: Option<Amount>, // Read-only.
+
+    // Fields that have no typed counterpart above, e.g. `RowId`, kept so that a
+    // row that is fetched and sent back does not lose them.
+    #[serde(flatten)]
+    #[serde(serialize_with = "serialize_unknown")]
+    pub unknown: HashMap<String, serde_json::Value>,
+}
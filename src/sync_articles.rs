@@ -0,0 +1,18 @@
+use crate::{
+    Article, ArticleListItem, ArticleSortBy, Error, Gateway, ListQuery, SortOrder, SyncCursor,
+};
+
+impl Gateway {
+    // The articles that have changed since the cursor last synced them.
+    pub async fn sync_articles(
+        &self,
+        cursor: &mut SyncCursor,
+    ) -> Result<Vec<ArticleListItem>, Error> {
+        let query = ListQuery::<Article>::new()
+            .sort_by(ArticleSortBy::ArticleNumber)
+            .sort_order(SortOrder::Ascending);
+
+        self.sync(cursor, query, |item: &ArticleListItem| &item.article_number)
+            .await
+    }
+}
@@ -0,0 +1,298 @@
+use crate::{Error, Gateway, ListQuery, Resource};
+use chrono::{DateTime, Duration, NaiveDateTime, Offset, TimeZone, Timelike, Utc};
+use chrono_tz::Europe::Stockholm;
+use futures::stream::TryStreamExt;
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::{HashMap, HashSet};
+
+// How far our clock may be ahead of Fortnox's. Records changed within this
+// margin before a sync are asked for again by the next one.
+const CLOCK_SKEW_SECS: i64 = 60;
+
+// Remembers how far each resource has been synced, so that the next sync only
+// returns records that have changed since. Serialize it to keep it between
+// runs; a new cursor returns every record.
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct SyncCursor {
+    resources: HashMap<String, Checkpoint>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+struct Checkpoint {
+    synced_at: DateTime<Utc>,
+
+    // The records changed shortly before a sync started are returned again by
+    // the next one, since Fortnox only filters on whole minutes and the clocks
+    // may differ. These are fingerprints of the whole records, not just of what
+    // the list shows, so that the ones that have not changed since can be
+    // skipped.
+    boundary: HashMap<String, String>,
+}
+
+impl SyncCursor {
+    pub fn new() -> SyncCursor {
+        SyncCursor::default()
+    }
+
+    // When the resource, e.g. `orders`, was last synced.
+    pub fn synced_at(&self, resource: &str) -> Option<DateTime<Utc>> {
+        self.resources.get(resource).map(|c| c.synced_at)
+    }
+
+    // Makes the next sync of the resource start over from scratch.
+    pub fn reset(&mut self, resource: &str) {
+        self.resources.remove(resource);
+    }
+}
+
+impl Gateway {
+    // Lists the records of `R` that have changed since the cursor's last sync
+    // of it, and moves the cursor forward. The cursor is left untouched if
+    // anything fails, so the next sync picks up the same changes.
+    //
+    // `query` should sort by the records' numbers. A stable order keeps records
+    // from moving between pages while they are fetched.
+    pub(crate) async fn sync<R: Resource, T: DeserializeOwned + Serialize>(
+        &self,
+        cursor: &mut SyncCursor,
+        query: ListQuery<R>,
+        id: impl Fn(&T) -> &str,
+    ) -> Result<Vec<T>, Error> {
+        let started = Utc::now();
+        let checkpoint = cursor.resources.get(R::PATH);
+
+        let changed = match checkpoint {
+            Some(checkpoint) => query
+                .clone()
+                .last_modified(last_modified(checkpoint.synced_at, started)),
+            None => query.clone(),
+        };
+        let fetched: Vec<T> = self
+            .paginate(R::PATH, R::LIST_KEY, &changed)
+            .try_collect()
+            .await?;
+
+        let mut fingerprints = HashMap::new();
+        let mut items = vec![];
+        for item in fetched {
+            let id = id(&item).to_string();
+            // A record that moves between pages while they are fetched shows
+            // up twice.
+            if fingerprints.contains_key(&id) {
+                continue;
+            }
+            fingerprints.insert(id.clone(), fingerprint(&item)?);
+
+            // The list only has a few of a record's fields, so it takes the
+            // whole record to tell whether it has changed since.
+            if let Some(stored) = checkpoint.and_then(|c| c.boundary.get(&id)) {
+                if self.record_fingerprint::<R>(&id).await?.as_ref() == Some(stored) {
+                    continue;
+                }
+            }
+            items.push(item);
+        }
+
+        // Find the records the next sync will be given again, and remember the
+        // ones that are unchanged from what was just returned. Anything changed
+        // in the meantime has a new fingerprint and is returned next time.
+        let boundary_query = query.last_modified(last_modified(started, started));
+        let again: Vec<T> = self
+            .paginate(R::PATH, R::LIST_KEY, &boundary_query)
+            .try_collect()
+            .await?;
+
+        let mut boundary = HashMap::new();
+        let mut ids = HashSet::new();
+        for item in again {
+            let id = id(&item).to_string();
+            if !ids.insert(id.clone()) {
+                continue;
+            }
+            if fingerprints.get(&id) != Some(&fingerprint(&item)?) {
+                continue;
+            }
+            if let Some(fingerprint) = self.record_fingerprint::<R>(&id).await? {
+                boundary.insert(id, fingerprint);
+            }
+        }
+
+        cursor.resources.insert(
+            R::PATH.to_string(),
+            Checkpoint {
+                synced_at: started,
+                boundary,
+            },
+        );
+        Ok(items)
+    }
+
+    // The fingerprint of the whole record, or None if it has been deleted.
+    async fn record_fingerprint<R: Resource>(&self, id: &str) -> Result<Option<String>, Error> {
+        let url = self.record_url(R::PATH, id)?;
+        match self.http_get::<Value>(&url).await {
+            Ok(record) => Ok(Some(fingerprint(&record)?)),
+            Err(err) if err.is_not_found() => Ok(None),
+            Err(err) => Err(err),
+        }
+    }
+}
+
+// The lastmodified timestamp that covers everything changed after `since`.
+// Fortnox stamps records in Swedish local time, truncated to the minute.
+fn last_modified(since: DateTime<Utc>, now: DateTime<Utc>) -> NaiveDateTime {
+    let from = since - Duration::seconds(CLOCK_SKEW_SECS);
+    let local = from.with_timezone(&Stockholm).naive_local();
+    let mut local = local - Duration::seconds(i64::from(local.second()));
+    local = local.with_nanosecond(0).unwrap_or(local);
+
+    // When the clocks have been turned back since (at the end of summer time),
+    // a record changed after `since` can have an earlier local timestamp.
+    let turned_back = utc_offset(from) - min_utc_offset(from, now);
+    local - Duration::seconds(i64::from(turned_back))
+}
+
+fn utc_offset(at: DateTime<Utc>) -> i32 {
+    Stockholm
+        .offset_from_utc_datetime(&at.naive_utc())
+        .fix()
+        .local_minus_utc()
+}
+
+fn min_utc_offset(from: DateTime<Utc>, to: DateTime<Utc>) -> i32 {
+    // Offset changes are months apart, so sampling every day finds them all.
+    let mut min = utc_offset(from).min(utc_offset(to));
+    let mut at = from;
+    while at < to {
+        min = min.min(utc_offset(at));
+        at += Duration::days(1);
+    }
+    min
+}
+
+// A stable fingerprint of a record's content. FNV-1a, since the standard
+// library's hashers may change between releases and cursors are persisted.
+fn fingerprint(item: &impl Serialize) -> Result<String, Error> {
+    let json = match serde_json::to_vec(item) {
+        Ok(json) => json,
+        Err(err) => {
+            return Err(Error::serialization(format!(
+                "Could not serialize record for sync ({}).",
+                err
+            ))
+            .with_source(err))
+        }
+    };
+
+    let mut hash: u64 = 0xcbf2_9ce4_8422_2325;
+    for byte in json {
+        hash ^= u64::from(byte);
+        hash = hash.wrapping_mul(0x0100_0000_01b3);
+    }
+    Ok(format!("{:016x}", hash))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::NaiveDate;
+
+    fn utc(y: i32, m: u32, d: u32, h: u32, min: u32, s: u32) -> DateTime<Utc> {
+        Utc.with_ymd_and_hms(y, m, d, h, min, s).unwrap()
+    }
+
+    fn local(y: i32, m: u32, d: u32, h: u32, min: u32) -> NaiveDateTime {
+        NaiveDate::from_ymd_opt(y, m, d)
+            .unwrap()
+            .and_hms_opt(h, min, 0)
+            .unwrap()
+    }
+
+    #[test]
+    fn converts_to_swedish_time_less_the_clock_skew() {
+        // CET in winter, CEST in summer.
+        let since = utc(2024, 1, 15, 10, 30, 0);
+        assert_eq!(
+            last_modified(since, since + Duration::hours(1)),
+            local(2024, 1, 15, 11, 29)
+        );
+
+        let since = utc(2024, 7, 1, 10, 30, 0);
+        assert_eq!(
+            last_modified(since, since + Duration::hours(1)),
+            local(2024, 7, 1, 12, 29)
+        );
+    }
+
+    #[test]
+    fn truncates_to_the_minute() {
+        let since = utc(2024, 1, 15, 10, 30, 59) + Duration::milliseconds(999);
+        assert_eq!(
+            last_modified(since, since + Duration::minutes(5)),
+            local(2024, 1, 15, 11, 29)
+        );
+
+        let since = utc(2024, 1, 15, 10, 31, 0);
+        assert_eq!(
+            last_modified(since, since + Duration::minutes(5)),
+            local(2024, 1, 15, 11, 30)
+        );
+    }
+
+    #[test]
+    fn goes_back_an_hour_when_the_clocks_are_turned_back_in_between() {
+        // 02:30 summer time. At 03:00 the clocks are turned back to 02:00, so a
+        // record changed at 01:30 UTC is stamped 02:30, the same as `since`.
+        let since = utc(2024, 10, 27, 0, 30, 0);
+        let now = utc(2024, 10, 27, 1, 30, 0);
+        assert_eq!(last_modified(since, now), local(2024, 10, 27, 1, 29));
+
+        // Also when the sync comes days later.
+        let since = utc(2024, 10, 1, 12, 0, 0);
+        let now = utc(2024, 11, 15, 12, 0, 0);
+        assert_eq!(last_modified(since, now), local(2024, 10, 1, 12, 59));
+    }
+
+    #[test]
+    fn stays_put_when_the_clocks_are_turned_forward_in_between() {
+        // 01:30 winter time. At 02:00 the clocks skip to 03:00, which only moves
+        // later timestamps further ahead.
+        let since = utc(2024, 3, 31, 0, 30, 0);
+        let now = utc(2024, 3, 31, 2, 0, 0);
+        assert_eq!(last_modified(since, now), local(2024, 3, 31, 1, 29));
+    }
+
+    #[test]
+    fn uses_the_offset_after_a_transition_that_has_passed() {
+        // Both in winter time, the week after the clocks were turned back.
+        let since = utc(2024, 11, 3, 9, 0, 0);
+        let now = utc(2024, 11, 3, 10, 0, 0);
+        assert_eq!(last_modified(since, now), local(2024, 11, 3, 9, 59));
+    }
+
+    #[test]
+    fn finds_the_lowest_offset_in_between() {
+        assert_eq!(utc_offset(utc(2024, 7, 1, 0, 0, 0)), 7200);
+        assert_eq!(utc_offset(utc(2024, 1, 1, 0, 0, 0)), 3600);
+        assert_eq!(
+            min_utc_offset(utc(2024, 7, 1, 0, 0, 0), utc(2024, 8, 1, 0, 0, 0)),
+            7200
+        );
+        assert_eq!(
+            min_utc_offset(utc(2024, 7, 1, 0, 0, 0), utc(2025, 7, 1, 0, 0, 0)),
+            3600
+        );
+    }
+
+    #[test]
+    fn fingerprints_are_stable() {
+        let a = serde_json::json!({"Name": "Acme", "Email": "a@acme.se"});
+        let b = serde_json::json!({"Email": "a@acme.se", "Name": "Acme"});
+        let c = serde_json::json!({"Name": "Acme", "Email": "b@acme.se"});
+        assert_eq!(fingerprint(&a).unwrap(), fingerprint(&b).unwrap());
+        assert_ne!(fingerprint(&a).unwrap(), fingerprint(&c).unwrap());
+        assert_eq!(fingerprint(&a).unwrap().len(), 16);
+    }
+}
@@ -0,0 +1,20 @@
+use crate::{
+    Customer, CustomerListItem, CustomerSortBy, Error, Gateway, ListQuery, SortOrder, SyncCursor,
+};
+
+impl Gateway {
+    // The customers that have changed since the cursor last synced them.
+    pub async fn sync_customers(
+        &self,
+        cursor: &mut SyncCursor,
+    ) -> Result<Vec<CustomerListItem>, Error> {
+        let query = ListQuery::<Customer>::new()
+            .sort_by(CustomerSortBy::CustomerNumber)
+            .sort_order(SortOrder::Ascending);
+
+        self.sync(cursor, query, |item: &CustomerListItem| {
+            &item.customer_number
+        })
+        .await
+    }
+}
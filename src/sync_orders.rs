@@ -0,0 +1,13 @@
+use crate::{Error, Gateway, ListQuery, Order, OrderListItem, OrderSortBy, SortOrder, SyncCursor};
+
+impl Gateway {
+    // The orders that have changed since the cursor last synced them.
+    pub async fn sync_orders(&self, cursor: &mut SyncCursor) -> Result<Vec<OrderListItem>, Error> {
+        let query = ListQuery::<Order>::new()
+            .sort_by(OrderSortBy::DocumentNumber)
+            .sort_order(SortOrder::Ascending);
+
+        self.sync(cursor, query, |item: &OrderListItem| &item.document_number)
+            .await
+    }
+}
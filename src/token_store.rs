@@ -0,0 +1,216 @@
+use crate::{Error, TokenSet};
+use std::fs::{self, OpenOptions};
+use std::io::{ErrorKind, Write};
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::thread::sleep;
+use std::time::{Duration, SystemTime};
+
+const LOCK_RETRY_DELAY_MS: u64 = 10;
+const LOCK_TIMEOUT_MS: u64 = 5000;
+// A lock file older than this is assumed to have been left behind by a process
+// that died while holding it.
+const STALE_LOCK_SECS: u64 = 30;
+
+// The gateway calls a token store from a blocking thread, so implementations are
+// free to block, e.g. on file or database I/O.
+pub trait TokenStore: Send + Sync {
+    fn load(&self) -> Result<Option<TokenSet>, Error>;
+
+    fn save(&self, tokens: &TokenSet) -> Result<(), Error>;
+
+    // Stores `new` only if the stored refresh token is still `current`, and
+    // returns whether it did. A `false` means that someone else has already
+    // rotated the tokens.
+    fn compare_and_swap(&self, current: &str, new: &TokenSet) -> Result<bool, Error>;
+}
+
+pub struct InMemoryTokenStore {
+    tokens: Mutex<Option<TokenSet>>,
+}
+
+impl InMemoryTokenStore {
+    pub fn new(tokens: Option<TokenSet>) -> InMemoryTokenStore {
+        InMemoryTokenStore {
+            tokens: Mutex::new(tokens),
+        }
+    }
+
+    fn lock(&self) -> Result<std::sync::MutexGuard<'_, Option<TokenSet>>, Error> {
+        match self.tokens.lock() {
+            Ok(guard) => Ok(guard),
+            Err(_) => Err(Error::unspecified(
+                "Token store lock is poisoned.".to_string(),
+            )),
+        }
+    }
+}
+
+impl TokenStore for InMemoryTokenStore {
+    fn load(&self) -> Result<Option<TokenSet>, Error> {
+        Ok(self.lock()?.clone())
+    }
+
+    fn save(&self, tokens: &TokenSet) -> Result<(), Error> {
+        *self.lock()? = Some(tokens.clone());
+        Ok(())
+    }
+
+    fn compare_and_swap(&self, current: &str, new: &TokenSet) -> Result<bool, Error> {
+        let mut tokens = self.lock()?;
+        match &*tokens {
+            Some(stored) if stored.refresh_token != current => Ok(false),
+            _ => {
+                *tokens = Some(new.clone());
+                Ok(true)
+            }
+        }
+    }
+}
+
+// Stores the tokens as JSON in a file that can be shared by several processes.
+// Writes go through a temporary file that is renamed into place, so readers
+// never see a half-written file, and compare-and-swap is serialized with a lock
+// file next to it.
+pub struct FileTokenStore {
+    path: PathBuf,
+}
+
+impl FileTokenStore {
+    pub fn new(path: impl Into<PathBuf>) -> FileTokenStore {
+        FileTokenStore { path: path.into() }
+    }
+
+    fn sibling(&self, extension: &str) -> PathBuf {
+        let mut name = self.path.clone().into_os_string();
+        name.push(".");
+        name.push(extension);
+        PathBuf::from(name)
+    }
+
+    fn write(&self, tokens: &TokenSet) -> Result<(), Error> {
+        let json = match serde_json::to_vec_pretty(tokens) {
+            Ok(json) => json,
+            Err(err) => {
+                return Err(
+                    Error::serialization(format!("Could not serialize tokens ({}).", err))
+                        .with_source(err),
+                )
+            }
+        };
+
+        let tmp = self.sibling(&format!("{}.tmp", std::process::id()));
+        let res = fs::File::create(&tmp)
+            .and_then(|mut file| {
+                file.write_all(&json)?;
+                file.sync_all()
+            })
+            .and_then(|_| fs::rename(&tmp, &self.path));
+        if let Err(err) = res {
+            let _ = fs::remove_file(&tmp);
+            return Err(Error::unspecified(format!(
+                "Could not write token file {} ({}).",
+                self.path.display(),
+                err
+            ))
+            .with_source(err));
+        }
+        Ok(())
+    }
+
+    fn lock(&self) -> Result<FileLock, Error> {
+        let path = self.sibling("lock");
+        let mut waited = 0;
+        loop {
+            match OpenOptions::new().write(true).create_new(true).open(&path) {
+                Ok(_) => return Ok(FileLock { path }),
+                Err(err) if err.kind() == ErrorKind::AlreadyExists => {
+                    if is_stale(&path) {
+                        let _ = fs::remove_file(&path);
+                        continue;
+                    }
+                    if waited >= LOCK_TIMEOUT_MS {
+                        return Err(Error::unspecified(format!(
+                            "Timed out waiting for token lock {}.",
+                            path.display()
+                        )));
+                    }
+                    sleep(Duration::from_millis(LOCK_RETRY_DELAY_MS));
+                    waited += LOCK_RETRY_DELAY_MS;
+                }
+                Err(err) => {
+                    return Err(Error::unspecified(format!(
+                        "Could not create token lock {} ({}).",
+                        path.display(),
+                        err
+                    ))
+                    .with_source(err))
+                }
+            }
+        }
+    }
+}
+
+impl TokenStore for FileTokenStore {
+    fn load(&self) -> Result<Option<TokenSet>, Error> {
+        let json = match fs::read(&self.path) {
+            Ok(json) => json,
+            Err(err) if err.kind() == ErrorKind::NotFound => return Ok(None),
+            Err(err) => {
+                return Err(Error::unspecified(format!(
+                    "Could not read token file {} ({}).",
+                    self.path.display(),
+                    err
+                ))
+                .with_source(err))
+            }
+        };
+
+        match serde_json::from_slice(&json) {
+            Ok(tokens) => Ok(Some(tokens)),
+            Err(err) => Err(Error::parse(format!(
+                "Could not parse token file {} ({}).",
+                self.path.display(),
+                err
+            ))
+            .with_source(err)),
+        }
+    }
+
+    fn save(&self, tokens: &TokenSet) -> Result<(), Error> {
+        let _lock = self.lock()?;
+        self.write(tokens)
+    }
+
+    fn compare_and_swap(&self, current: &str, new: &TokenSet) -> Result<bool, Error> {
+        let _lock = self.lock()?;
+        match self.load()? {
+            Some(stored) if stored.refresh_token != current => Ok(false),
+            _ => {
+                self.write(new)?;
+                Ok(true)
+            }
+        }
+    }
+}
+
+struct FileLock {
+    path: PathBuf,
+}
+
+impl Drop for FileLock {
+    fn drop(&mut self) {
+        let _ = fs::remove_file(&self.path);
+    }
+}
+
+fn is_stale(path: &Path) -> bool {
+    let modified = match fs::metadata(path).and_then(|m| m.modified()) {
+        Ok(modified) => modified,
+        Err(_) => return false,
+    };
+    match SystemTime::now().duration_since(modified) {
+        Ok(age) => age > Duration::from_secs(STALE_LOCK_SECS),
+        Err(_) => false,
+    }
+}
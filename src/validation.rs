@@ -0,0 +1,517 @@
+use crate::{ApiErrorCode, EmailInformation, Row};
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+// A cap on the rows of an order, offer or invoice, to catch a runaway document
+// before it is sent. Fortnox does not publish a limit, so it is a guess.
+const MAX_ROWS: usize = 500;
+
+// Whether a record is about to be created or updated. A new record needs its
+// required fields, while an update needs the number that identifies it.
+//
+// The models' `validate` methods catch likely mistakes, such as overlong
+// fields, invalid characters and missing numbers, before a request is sent
+// rather than as an API error. The limits are this crate's own, not taken from
+// a Fortnox specification, so a record that passes may still be refused.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, Eq, PartialEq)]
+pub enum ValidationMode {
+    Create,
+    Update,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Eq, PartialEq)]
+pub enum FieldErrorKind {
+    Required,
+    // The most characters the field can hold.
+    TooLong(usize),
+    InvalidCharacters,
+    NotNumeric,
+    // E.g. a country code or an e-mail address that is not well-formed.
+    InvalidFormat,
+    // The most rows a document can have.
+    TooManyRows(usize),
+}
+
+// A field that Fortnox would refuse, found before sending the request.
+#[derive(Serialize, Deserialize, Debug, Clone, Eq, PartialEq)]
+pub struct FieldError {
+    // Named as in Fortnox's JSON, e.g. `Name` or `OrderRows[2].Description`.
+    pub field: String,
+    pub kind: FieldErrorKind,
+}
+
+impl FieldError {
+    // The code Fortnox answers with for the same mistake, where there is one.
+    pub fn api_error_code(&self) -> Option<ApiErrorCode> {
+        match self.kind {
+            FieldErrorKind::InvalidCharacters => Some(ApiErrorCode::InvalidCharacters),
+            FieldErrorKind::NotNumeric => Some(ApiErrorCode::NotNumeric),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for FieldError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self.kind {
+            FieldErrorKind::Required => write!(f, "{} is required.", self.field),
+            FieldErrorKind::TooLong(max) => {
+                write!(f, "{} can be at most {} characters long.", self.field, max)
+            }
+            FieldErrorKind::InvalidCharacters => {
+                write!(f, "{} contains invalid characters.", self.field)
+            }
+            FieldErrorKind::NotNumeric => write!(f, "{} must be numeric.", self.field),
+            FieldErrorKind::InvalidFormat => write!(f, "{} is not well-formed.", self.field),
+            FieldErrorKind::TooManyRows(max) => {
+                write!(f, "{} can have at most {} rows.", self.field, max)
+            }
+        }
+    }
+}
+
+// Collects the field errors of a record. Every check passes for a field that is
+// None, except `required`.
+pub(crate) struct Validator {
+    mode: ValidationMode,
+    errors: Vec<FieldError>,
+}
+
+impl Validator {
+    pub(crate) fn new(mode: ValidationMode) -> Validator {
+        Validator {
+            mode,
+            errors: Vec::new(),
+        }
+    }
+
+    pub(crate) fn mode(&self) -> ValidationMode {
+        self.mode
+    }
+
+    pub(crate) fn finish(self) -> Result<(), Vec<FieldError>> {
+        if self.errors.is_empty() {
+            Ok(())
+        } else {
+            Err(self.errors)
+        }
+    }
+
+    fn push(&mut self, field: &str, kind: FieldErrorKind) {
+        self.errors.push(FieldError {
+            field: field.to_string(),
+            kind,
+        });
+    }
+
+    pub(crate) fn required(&mut self, field: &str, value: Option<&str>) {
+        match value {
+            Some(value) if !value.trim().is_empty() => {}
+            _ => self.push(field, FieldErrorKind::Required),
+        }
+    }
+
+    // Free text. Only line breaks and tabs are allowed among the control
+    // characters.
+    pub(crate) fn text(&mut self, field: &str, value: Option<&str>, max: usize) {
+        let value = match value {
+            Some(value) => value,
+            None => return,
+        };
+        if value.chars().count() > max {
+            self.push(field, FieldErrorKind::TooLong(max));
+        }
+        if value
+            .chars()
+            .any(|c| c.is_control() && !matches!(c, '\n' | '\r' | '\t'))
+        {
+            self.push(field, FieldErrorKind::InvalidCharacters);
+        }
+    }
+
+    // A customer, article or supplier number: letters, digits and a few
+    // separators, but no spaces.
+    pub(crate) fn identifier(&mut self, field: &str, value: Option<&str>, max: usize) {
+        let value = match value {
+            Some(value) => value,
+            None => return,
+        };
+        if value.chars().count() > max {
+            self.push(field, FieldErrorKind::TooLong(max));
+        }
+        if !value
+            .chars()
+            .all(|c| c.is_alphanumeric() || matches!(c, '-' | '_' | '+' | '/' | '.'))
+        {
+            self.push(field, FieldErrorKind::InvalidCharacters);
+        }
+    }
+
+    pub(crate) fn numeric(&mut self, field: &str, value: Option<&str>, max: usize) {
+        let value = match value {
+            Some(value) => value,
+            None => return,
+        };
+        if value.chars().count() > max {
+            self.push(field, FieldErrorKind::TooLong(max));
+        }
+        if !value.chars().all(|c| c.is_ascii_digit()) {
+            self.push(field, FieldErrorKind::NotNumeric);
+        }
+    }
+
+    // Two letter ISO 3166 code, e.g. "SE".
+    pub(crate) fn country_code(&mut self, field: &str, value: Option<&str>) {
+        let value = match value {
+            Some(value) => value,
+            None => return,
+        };
+        if value.len() != 2 || !value.chars().all(|c| c.is_ascii_uppercase()) {
+            self.push(field, FieldErrorKind::InvalidFormat);
+        }
+    }
+
+    // One or more e-mail addresses, separated by commas.
+    pub(crate) fn emails(&mut self, field: &str, value: Option<&str>, max: usize) {
+        let value = match value {
+            Some(value) => value,
+            None => return,
+        };
+        if value.chars().count() > max {
+            self.push(field, FieldErrorKind::TooLong(max));
+        }
+        if !value.split(',').all(|address| is_email(address.trim())) {
+            self.push(field, FieldErrorKind::InvalidFormat);
+        }
+    }
+
+    pub(crate) fn rows(&mut self, field: &str, rows: &[Row]) {
+        if rows.len() > MAX_ROWS {
+            self.push(field, FieldErrorKind::TooManyRows(MAX_ROWS));
+        }
+        for (i, row) in rows.iter().enumerate() {
+            let name = |row_field: &str| format!("{}[{}].{}", field, i, row_field);
+            self.identifier(&name("ArticleNumber"), row.article_number.as_deref(), 50);
+            self.text(&name("Description"), row.description.as_deref(), 50);
+            self.text(&name("Unit"), row.unit.as_deref(), 50);
+            self.text(&name("CostCenter"), row.cost_center.as_deref(), 50);
+            self.text(&name("Project"), row.project.as_deref(), 50);
+            match row.discount_type.as_deref() {
+                None | Some("AMOUNT") | Some("PERCENT") => {}
+                Some(_) => self.push(&name("DiscountType"), FieldErrorKind::InvalidFormat),
+            }
+        }
+    }
+
+    pub(crate) fn email_information(&mut self, email_information: Option<&EmailInformation>) {
+        let email = match email_information {
+            Some(email) => email,
+            None => return,
+        };
+        let name = |field: &str| format!("EmailInformation.{}", field);
+        self.emails(
+            &name("EmailAddressFrom"),
+            email.email_address_from.as_deref(),
+            100,
+        );
+        self.emails(
+            &name("EmailAddressTo"),
+            email.email_address_to.as_deref(),
+            1024,
+        );
+        self.emails(
+            &name("EmailAddressCC"),
+            email.email_address_cc.as_deref(),
+            1024,
+        );
+        self.emails(
+            &name("EmailAddressBCC"),
+            email.email_address_bcc.as_deref(),
+            1024,
+        );
+        self.text(&name("EmailSubject"), email.email_subject.as_deref(), 100);
+    }
+}
+
+// Deliberately loose: something before and after a single @, a dot in the
+// domain and no spaces.
+fn is_email(address: &str) -> bool {
+    let (local, domain) = match address.split_once('@') {
+        Some(parts) => parts,
+        None => return false,
+    };
+    !local.is_empty()
+        && !domain.contains('@')
+        && domain.contains('.')
+        && !domain.starts_with('.')
+        && !domain.ends_with('.')
+        && !address.chars().any(char::is_whitespace)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Article, Currency, Customer, Order};
+    use chrono::NaiveDate;
+
+    fn errors(v: Validator) -> Vec<(String, FieldErrorKind)> {
+        match v.finish() {
+            Ok(()) => vec![],
+            Err(errors) => errors.into_iter().map(|e| (e.field, e.kind)).collect(),
+        }
+    }
+
+    fn error(field: &str, kind: FieldErrorKind) -> (String, FieldErrorKind) {
+        (field.to_string(), kind)
+    }
+
+    fn order(rows: Vec<Row>) -> Order {
+        let date = NaiveDate::from_ymd_opt(2024, 1, 15).unwrap();
+        Order::new("1".to_string(), rows, Currency::SEK, true, date, date)
+    }
+
+    #[test]
+    fn accepts_well_formed_emails() {
+        for address in &[
+            "a@b.se",
+            "first.last+tag@example.co.uk",
+            "a@b.se, c@d.se",
+            "a@b.se,c@d.se",
+        ] {
+            let mut v = Validator::new(ValidationMode::Create);
+            v.emails("Email", Some(address), 1024);
+            assert_eq!(errors(v), vec![], "{}", address);
+        }
+    }
+
+    #[test]
+    fn rejects_malformed_emails() {
+        for address in &[
+            "",
+            "a",
+            "a@",
+            "@b.se",
+            "a@b",
+            "a@@b.se",
+            "a@b@c.se",
+            "a@.se",
+            "a@b.",
+            "a b@c.se",
+            "a@b.se,",
+            "a@b.se;c@d.se",
+        ] {
+            let mut v = Validator::new(ValidationMode::Create);
+            v.emails("Email", Some(address), 1024);
+            assert_eq!(
+                errors(v),
+                vec![error("Email", FieldErrorKind::InvalidFormat)],
+                "{}",
+                address
+            );
+        }
+    }
+
+    #[test]
+    fn limits_the_length_of_email_lists() {
+        let mut v = Validator::new(ValidationMode::Create);
+        v.emails("Email", Some("abc@example.se"), 10);
+        assert_eq!(errors(v), vec![error("Email", FieldErrorKind::TooLong(10))]);
+    }
+
+    #[test]
+    fn checks_country_codes() {
+        for code in &["SE", "NO", "GB"] {
+            let mut v = Validator::new(ValidationMode::Create);
+            v.country_code("CountryCode", Some(code));
+            assert_eq!(errors(v), vec![], "{}", code);
+        }
+        for code in &["", "se", "S", "SWE", "S1", "Sverige"] {
+            let mut v = Validator::new(ValidationMode::Create);
+            v.country_code("CountryCode", Some(code));
+            assert_eq!(
+                errors(v),
+                vec![error("CountryCode", FieldErrorKind::InvalidFormat)],
+                "{}",
+                code
+            );
+        }
+    }
+
+    #[test]
+    fn checks_text_identifiers_and_numbers() {
+        let mut v = Validator::new(ValidationMode::Create);
+        v.text("Comments", Some("Two\nlines\twith tab"), 100);
+        v.text("Name", Some("Åsa Öberg"), 9);
+        v.identifier("ArticleNumber", Some("A-1_2+3/4.5"), 50);
+        v.numeric("OCR", Some("0123"), 4);
+        v.text("Unset", None, 0);
+        assert_eq!(errors(v), vec![]);
+
+        let mut v = Validator::new(ValidationMode::Create);
+        v.text("Name", Some("Åsa Öberg"), 8);
+        v.text("Comments", Some("Bell\u{7}"), 100);
+        v.identifier("ArticleNumber", Some("A 1"), 50);
+        v.numeric("OCR", Some("12a"), 2);
+        assert_eq!(
+            errors(v),
+            vec![
+                error("Name", FieldErrorKind::TooLong(8)),
+                error("Comments", FieldErrorKind::InvalidCharacters),
+                error("ArticleNumber", FieldErrorKind::InvalidCharacters),
+                error("OCR", FieldErrorKind::TooLong(2)),
+                error("OCR", FieldErrorKind::NotNumeric),
+            ]
+        );
+    }
+
+    #[test]
+    fn requires_non_blank_values() {
+        let mut v = Validator::new(ValidationMode::Create);
+        v.required("A", None);
+        v.required("B", Some(""));
+        v.required("C", Some("  "));
+        v.required("D", Some("x"));
+        assert_eq!(
+            errors(v),
+            vec![
+                error("A", FieldErrorKind::Required),
+                error("B", FieldErrorKind::Required),
+                error("C", FieldErrorKind::Required),
+            ]
+        );
+    }
+
+    #[test]
+    fn limits_the_number_of_rows() {
+        let rows = vec![Row::default(); MAX_ROWS];
+        assert!(order(rows).validate(ValidationMode::Create).is_ok());
+
+        let rows = vec![Row::default(); MAX_ROWS + 1];
+        assert_eq!(
+            order(rows).validate(ValidationMode::Create),
+            Err(vec![FieldError {
+                field: "OrderRows".to_string(),
+                kind: FieldErrorKind::TooManyRows(MAX_ROWS),
+            }])
+        );
+    }
+
+    #[test]
+    fn names_the_row_of_a_row_error() {
+        let rows = vec![
+            Row::default(),
+            Row {
+                article_number: Some("A 1".to_string()),
+                discount_type: Some("FIXED".to_string()),
+                ..Row::default()
+            },
+        ];
+        let mut v = Validator::new(ValidationMode::Create);
+        v.rows("InvoiceRows", &rows);
+        assert_eq!(
+            errors(v),
+            vec![
+                error(
+                    "InvoiceRows[1].ArticleNumber",
+                    FieldErrorKind::InvalidCharacters
+                ),
+                error("InvoiceRows[1].DiscountType", FieldErrorKind::InvalidFormat),
+            ]
+        );
+    }
+
+    #[test]
+    fn checks_email_information() {
+        let email = EmailInformation {
+            email_address_to: Some("a@b.se".to_string()),
+            email_address_cc: Some("not an address".to_string()),
+            ..EmailInformation::default()
+        };
+        let mut v = Validator::new(ValidationMode::Create);
+        v.email_information(Some(&email));
+        assert_eq!(
+            errors(v),
+            vec![error(
+                "EmailInformation.EmailAddressCC",
+                FieldErrorKind::InvalidFormat
+            )]
+        );
+    }
+
+    #[test]
+    fn requires_a_name_to_create_and_a_number_to_update() {
+        let mut customer = Customer::new(String::new());
+        assert_eq!(
+            customer.validate(ValidationMode::Create),
+            Err(vec![FieldError {
+                field: "Name".to_string(),
+                kind: FieldErrorKind::Required,
+            }])
+        );
+        assert_eq!(
+            customer.validate(ValidationMode::Update),
+            Err(vec![FieldError {
+                field: "CustomerNumber".to_string(),
+                kind: FieldErrorKind::Required,
+            }])
+        );
+
+        customer.name = "Acme".to_string();
+        assert!(customer.validate(ValidationMode::Create).is_ok());
+        customer.customer_number = Some("1".to_string());
+        assert!(customer.validate(ValidationMode::Update).is_ok());
+
+        let mut article = Article::new("Widget".to_string());
+        assert!(article.validate(ValidationMode::Create).is_ok());
+        assert!(article.validate(ValidationMode::Update).is_err());
+        article.article_number = Some("W1".to_string());
+        assert!(article.validate(ValidationMode::Update).is_ok());
+    }
+
+    #[test]
+    fn requires_a_document_number_to_update() {
+        let mut order = order(vec![]);
+        assert!(order.validate(ValidationMode::Create).is_ok());
+        assert_eq!(
+            order.validate(ValidationMode::Update),
+            Err(vec![FieldError {
+                field: "DocumentNumber".to_string(),
+                kind: FieldErrorKind::Required,
+            }])
+        );
+        order.document_number = Some("12".to_string());
+        assert!(order.validate(ValidationMode::Update).is_ok());
+    }
+
+    #[test]
+    fn maps_to_api_error_codes() {
+        let code = |kind| {
+            FieldError {
+                field: "Name".to_string(),
+                kind,
+            }
+            .api_error_code()
+        };
+        assert_eq!(
+            code(FieldErrorKind::InvalidCharacters),
+            Some(ApiErrorCode::InvalidCharacters)
+        );
+        assert_eq!(
+            code(FieldErrorKind::NotNumeric),
+            Some(ApiErrorCode::NotNumeric)
+        );
+        assert_eq!(code(FieldErrorKind::Required), None);
+        assert_eq!(code(FieldErrorKind::TooLong(10)), None);
+        assert_eq!(code(FieldErrorKind::InvalidFormat), None);
+        assert_eq!(code(FieldErrorKind::TooManyRows(MAX_ROWS)), None);
+    }
+
+    #[test]
+    fn describes_errors() {
+        let error = FieldError {
+            field: "OrderRows".to_string(),
+            kind: FieldErrorKind::TooManyRows(500),
+        };
+        assert_eq!(error.to_string(), "OrderRows can have at most 500 rows.");
+    }
+}
@@ -0,0 +1,25 @@
+use serde::{Deserialize, Serialize};
+
+// How VAT is handled on the customer's documents.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, Eq, PartialEq)]
+pub enum VatType {
+    // Swedish VAT.
+    #[serde(rename = "SEVAT")]
+    SeVat,
+
+    // Domestic reverse charge, e.g. for construction services.
+    #[serde(rename = "SEREVERSEDVAT")]
+    SeReversedVat,
+
+    // Reverse charge for a business in another EU country.
+    #[serde(rename = "EUREVERSEDVAT")]
+    EuReversedVat,
+
+    // VAT charged to a consumer in another EU country.
+    #[serde(rename = "EUVAT")]
+    EuVat,
+
+    // Outside the EU.
+    #[serde(rename = "EXPORT")]
+    Export,
+}
@@ -0,0 +1,180 @@
+#![allow(dead_code)]
+
+// A minimal HTTP server that answers with scripted responses, in order, and
+// records the requests it receives.
+use std::sync::{Arc, Mutex};
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::{TcpListener, TcpStream};
+
+#[derive(Debug, Clone)]
+pub struct Request {
+    pub method: String,
+    pub path: String,
+    pub headers: Vec<(String, String)>,
+    pub body: String,
+}
+
+impl Request {
+    pub fn header(&self, name: &str) -> Option<&str> {
+        self.headers
+            .iter()
+            .find(|(n, _)| n.eq_ignore_ascii_case(name))
+            .map(|(_, v)| v.as_str())
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Response {
+    pub status: u16,
+    pub headers: Vec<(String, String)>,
+    pub body: String,
+}
+
+impl Response {
+    pub fn json(status: u16, body: &str) -> Response {
+        Response {
+            status,
+            headers: vec![],
+            body: body.to_string(),
+        }
+    }
+
+    pub fn header(mut self, name: &str, value: &str) -> Response {
+        self.headers.push((name.to_string(), value.to_string()));
+        self
+    }
+}
+
+pub fn token(access_token: &str, refresh_token: &str) -> Response {
+    Response::json(
+        200,
+        &format!(
+            r#"{{"access_token":"{}","refresh_token":"{}","scope":"customer","expires_in":3600,"token_type":"bearer"}}"#,
+            access_token, refresh_token
+        ),
+    )
+}
+
+pub fn customer(customer_number: &str) -> Response {
+    Response::json(
+        200,
+        &format!(
+            r#"{{"Customer":{{"CustomerNumber":"{}","Name":"Acme"}}}}"#,
+            customer_number
+        ),
+    )
+}
+
+pub struct MockServer {
+    pub url: String,
+    requests: Arc<Mutex<Vec<Request>>>,
+}
+
+impl MockServer {
+    pub async fn start(responses: Vec<Response>) -> MockServer {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}", listener.local_addr().unwrap());
+        let requests = Arc::new(Mutex::new(vec![]));
+        let responses = Arc::new(Mutex::new(responses.into_iter()));
+
+        let recorded = requests.clone();
+        tokio::spawn(async move {
+            loop {
+                let (stream, _) = match listener.accept().await {
+                    Ok(conn) => conn,
+                    Err(_) => return,
+                };
+                let recorded = recorded.clone();
+                let responses = responses.clone();
+                tokio::spawn(async move {
+                    serve(stream, recorded, responses).await;
+                });
+            }
+        });
+
+        MockServer { url, requests }
+    }
+
+    pub fn requests(&self) -> Vec<Request> {
+        self.requests.lock().unwrap().clone()
+    }
+}
+
+async fn serve(
+    mut stream: TcpStream,
+    recorded: Arc<Mutex<Vec<Request>>>,
+    responses: Arc<Mutex<std::vec::IntoIter<Response>>>,
+) {
+    let mut buf = vec![];
+    let mut chunk = [0u8; 4096];
+    loop {
+        let request = match parse(&buf) {
+            Some((request, len)) => {
+                buf.drain(..len);
+                request
+            }
+            None => match stream.read(&mut chunk).await {
+                Ok(0) | Err(_) => return,
+                Ok(n) => {
+                    buf.extend_from_slice(&chunk[..n]);
+                    continue;
+                }
+            },
+        };
+        recorded.lock().unwrap().push(request);
+
+        let response = responses
+            .lock()
+            .unwrap()
+            .next()
+            .unwrap_or_else(|| Response::json(500, "No more scripted responses."));
+        let mut head = format!(
+            "HTTP/1.1 {} Mock\r\nContent-Type: application/json\r\nContent-Length: {}\r\n",
+            response.status,
+            response.body.len()
+        );
+        for (name, value) in &response.headers {
+            head.push_str(&format!("{}: {}\r\n", name, value));
+        }
+        head.push_str("\r\n");
+        if stream.write_all(head.as_bytes()).await.is_err()
+            || stream.write_all(response.body.as_bytes()).await.is_err()
+        {
+            return;
+        }
+    }
+}
+
+// The request at the start of `buf` and its length, once all of it is there.
+fn parse(buf: &[u8]) -> Option<(Request, usize)> {
+    let end = buf.windows(4).position(|w| w == b"\r\n\r\n")? + 4;
+    let head = String::from_utf8_lossy(&buf[..end]).into_owned();
+    let mut lines = head.split("\r\n");
+    let mut start = lines.next()?.split(' ');
+    let method = start.next()?.to_string();
+    let path = start.next()?.to_string();
+
+    let headers: Vec<(String, String)> = lines
+        .filter_map(|line| line.split_once(':'))
+        .map(|(name, value)| (name.trim().to_string(), value.trim().to_string()))
+        .collect();
+    let len = headers
+        .iter()
+        .find(|(name, _)| name.eq_ignore_ascii_case("content-length"))
+        .and_then(|(_, value)| value.parse().ok())
+        .unwrap_or(0);
+    if buf.len() < end + len {
+        return None;
+    }
+
+    let body = String::from_utf8_lossy(&buf[end..end + len]).into_owned();
+    Some((
+        Request {
+            method,
+            path,
+            headers,
+            body,
+        },
+        end + len,
+    ))
+}
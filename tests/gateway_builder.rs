@@ -0,0 +1,127 @@
+mod common;
+
+use common::{customer, MockServer, Response};
+use fortnox::{ErrorKind, GatewayBuilder, RetryPolicy};
+use std::time::{Duration, Instant};
+
+fn builder(server: &MockServer) -> GatewayBuilder {
+    GatewayBuilder::new()
+        .access_token("token".to_string(), "secret".to_string())
+        .base_url(format!("{}/3/", server.url))
+        .https_only(false)
+}
+
+#[tokio::test]
+async fn sends_requests_to_the_base_url() {
+    let server = MockServer::start(vec![customer("1")]).await;
+
+    let gateway = builder(&server).build().await.unwrap();
+    let customer = gateway.get_customer("1").await.unwrap();
+    assert_eq!(customer.name, "Acme");
+
+    let requests = server.requests();
+    assert_eq!(requests.len(), 1);
+    assert_eq!(requests[0].method, "GET");
+    assert_eq!(requests[0].path, "/3/customers/1");
+    assert_eq!(requests[0].header("Access-Token"), Some("token"));
+    assert_eq!(requests[0].header("Client-Secret"), Some("secret"));
+}
+
+#[tokio::test]
+async fn percent_encodes_ids() {
+    let server = MockServer::start(vec![customer("A/1")]).await;
+
+    let gateway = builder(&server).build().await.unwrap();
+    gateway.get_customer("A/1 2").await.unwrap();
+    assert_eq!(server.requests()[0].path, "/3/customers/A%2F1%202");
+}
+
+#[tokio::test]
+async fn percent_encodes_ids_in_action_urls() {
+    let server = MockServer::start(vec![
+        Response::json(200, "%PDF-1.4"),
+        Response::json(200, "%PDF-1.4"),
+    ])
+    .await;
+
+    let gateway = builder(&server).build().await.unwrap();
+    gateway.print_order("12/3").await.unwrap();
+    gateway.preview_invoice("4 5?").await.unwrap();
+
+    let requests = server.requests();
+    assert_eq!(requests[0].path, "/3/orders/12%2F3/print");
+    assert_eq!(requests[1].path, "/3/invoices/4%205%3F/preview");
+}
+
+#[tokio::test]
+async fn refuses_plain_http_by_default() {
+    let server = MockServer::start(vec![customer("1")]).await;
+
+    let gateway = GatewayBuilder::new()
+        .access_token("token".to_string(), "secret".to_string())
+        .base_url(server.url.clone())
+        .retry_policy(RetryPolicy::none())
+        .build()
+        .await
+        .unwrap();
+    let err = gateway.get_customer("1").await.unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::Network);
+    assert!(server.requests().is_empty());
+}
+
+#[tokio::test]
+async fn waits_as_long_as_retry_after_asks() {
+    let server = MockServer::start(vec![
+        Response::json(429, "").header("Retry-After", "1"),
+        customer("1"),
+    ])
+    .await;
+
+    let gateway = builder(&server).build().await.unwrap();
+    let started = Instant::now();
+    gateway.get_customer("1").await.unwrap();
+    assert!(started.elapsed() >= Duration::from_secs(1));
+    assert_eq!(server.requests().len(), 2);
+}
+
+#[tokio::test]
+async fn caps_retry_after_at_max_delay() {
+    let server = MockServer::start(vec![
+        Response::json(429, "").header("Retry-After", "3600"),
+        customer("1"),
+    ])
+    .await;
+
+    let mut retry_policy = RetryPolicy::default();
+    retry_policy.max_delay = Duration::from_millis(50);
+    let gateway = builder(&server)
+        .retry_policy(retry_policy)
+        .build()
+        .await
+        .unwrap();
+    let started = Instant::now();
+    gateway.get_customer("1").await.unwrap();
+    assert!(started.elapsed() < Duration::from_secs(5));
+}
+
+#[tokio::test]
+async fn gives_up_after_max_attempts() {
+    let server = MockServer::start(vec![
+        Response::json(429, "").header("Retry-After", "0"),
+        Response::json(429, "").header("Retry-After", "0"),
+        customer("1"),
+    ])
+    .await;
+
+    let mut retry_policy = RetryPolicy::default();
+    retry_policy.max_attempts = 2;
+    let gateway = builder(&server)
+        .retry_policy(retry_policy)
+        .build()
+        .await
+        .unwrap();
+    let err = gateway.get_customer("1").await.unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::Throttling);
+    assert_eq!(err.status(), Some(429));
+    assert_eq!(server.requests().len(), 2);
+}
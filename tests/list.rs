@@ -0,0 +1,82 @@
+mod common;
+
+use common::{MockServer, Response};
+use fortnox::{Currency, GatewayBuilder, ListQuery, RetryPolicy};
+
+fn builder(server: &MockServer) -> GatewayBuilder {
+    GatewayBuilder::new()
+        .access_token("token".to_string(), "secret".to_string())
+        .base_url(server.url.clone())
+        .https_only(false)
+        .retry_policy(RetryPolicy::none())
+}
+
+// A page of list items that differ only in their number and currency.
+fn page(key: &str, number_field: &str, currencies: &[&str]) -> Response {
+    let items: Vec<String> = currencies
+        .iter()
+        .enumerate()
+        .map(|(i, currency)| {
+            format!(
+                r#"{{"{}":"{}","CustomerNumber":"1","Currency":"{}"}}"#,
+                number_field,
+                i + 1,
+                currency
+            )
+        })
+        .collect();
+    Response::json(
+        200,
+        &format!(
+            r#"{{"MetaInformation":{{"@TotalResources":{},"@TotalPages":1,"@CurrentPage":1}},"{}":[{}]}}"#,
+            items.len(),
+            key,
+            items.join(",")
+        ),
+    )
+}
+
+fn expected() -> Vec<Option<Currency>> {
+    vec![
+        Some(Currency::SEK),
+        Some(Currency::USD),
+        Some(Currency::EUR),
+        Some(Currency::Other("XYZ".to_string())),
+        None,
+    ]
+}
+
+const CURRENCIES: &[&str] = &["SEK", "USD", "EUR", "XYZ", ""];
+
+#[tokio::test]
+async fn lists_orders_in_any_currency() {
+    let server = MockServer::start(vec![page("Orders", "DocumentNumber", CURRENCIES)]).await;
+    let gateway = builder(&server).build().await.unwrap();
+
+    let orders = gateway.list_orders(&ListQuery::new()).await.unwrap();
+    let currencies: Vec<_> = orders.into_iter().map(|order| order.currency).collect();
+    assert_eq!(currencies, expected());
+}
+
+#[tokio::test]
+async fn lists_invoices_in_any_currency() {
+    let server = MockServer::start(vec![page("Invoices", "DocumentNumber", CURRENCIES)]).await;
+    let gateway = builder(&server).build().await.unwrap();
+
+    let invoices = gateway.list_invoices(&ListQuery::new()).await.unwrap();
+    let currencies: Vec<_> = invoices
+        .into_iter()
+        .map(|invoice| invoice.currency)
+        .collect();
+    assert_eq!(currencies, expected());
+}
+
+#[tokio::test]
+async fn lists_offers_in_any_currency() {
+    let server = MockServer::start(vec![page("Offers", "DocumentNumber", CURRENCIES)]).await;
+    let gateway = builder(&server).build().await.unwrap();
+
+    let offers = gateway.list_offers(&ListQuery::new()).await.unwrap();
+    let currencies: Vec<_> = offers.into_iter().map(|offer| offer.currency).collect();
+    assert_eq!(currencies, expected());
+}
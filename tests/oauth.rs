@@ -0,0 +1,331 @@
+mod common;
+
+use chrono::{Duration, Utc};
+use common::{customer, token, MockServer, Response};
+use fortnox::{
+    Customer, ErrorKind, Gateway, GatewayBuilder, InMemoryTokenStore, OAuthClient, RetryPolicy,
+    TokenSet, TokenStore,
+};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration as StdDuration, Instant};
+
+fn client(server: &MockServer) -> OAuthClient {
+    let mut client = OAuthClient::new("id".to_string(), "secret".to_string());
+    client.token_url = format!("{}/oauth-v1/token", server.url);
+    client.https_only = false;
+    client
+}
+
+fn builder(server: &MockServer) -> GatewayBuilder {
+    GatewayBuilder::new()
+        .base_url(server.url.clone())
+        .https_only(false)
+        .retry_policy(RetryPolicy::none())
+}
+
+// Collects the token sets passed to `on_refresh`.
+fn recorder() -> (
+    Arc<Mutex<Vec<TokenSet>>>,
+    impl Fn(&TokenSet) + Send + Sync + 'static,
+) {
+    let refreshed = Arc::new(Mutex::new(vec![]));
+    let recorded = refreshed.clone();
+    (refreshed, move |tokens: &TokenSet| {
+        recorded.lock().unwrap().push(tokens.clone())
+    })
+}
+
+#[tokio::test]
+async fn refreshes_when_built_and_sends_the_access_token() {
+    let server = MockServer::start(vec![token("a1", "r2"), customer("1")]).await;
+    let (refreshed, on_refresh) = recorder();
+
+    let gateway = builder(&server)
+        .oauth(client(&server), "r1".to_string(), on_refresh)
+        .build()
+        .await
+        .unwrap();
+    let customer = gateway.get_customer("1").await.unwrap();
+    assert_eq!(customer.customer_number.as_deref(), Some("1"));
+
+    let requests = server.requests();
+    assert_eq!(requests.len(), 2);
+    assert_eq!(requests[0].method, "POST");
+    assert_eq!(requests[0].path, "/oauth-v1/token");
+    assert!(requests[0].body.contains("grant_type=refresh_token"));
+    assert!(requests[0].body.contains("refresh_token=r1"));
+    assert!(requests[0]
+        .header("Authorization")
+        .unwrap()
+        .starts_with("Basic "));
+    assert_eq!(requests[1].path, "/customers/1");
+    assert_eq!(requests[1].header("Authorization"), Some("Bearer a1"));
+
+    let refreshed = refreshed.lock().unwrap();
+    assert_eq!(refreshed.len(), 1);
+    assert_eq!(refreshed[0].access_token, "a1");
+    assert_eq!(refreshed[0].refresh_token, "r2");
+}
+
+#[tokio::test]
+async fn refreshes_and_retries_once_after_a_401() {
+    let server = MockServer::start(vec![
+        token("a1", "r2"),
+        Response::json(401, r#"{"message":"unauthorized"}"#),
+        token("a2", "r3"),
+        customer("1"),
+    ])
+    .await;
+    let (refreshed, on_refresh) = recorder();
+
+    let gateway = builder(&server)
+        .oauth(client(&server), "r1".to_string(), on_refresh)
+        .build()
+        .await
+        .unwrap();
+    gateway.get_customer("1").await.unwrap();
+
+    let requests = server.requests();
+    assert_eq!(requests.len(), 4);
+    assert_eq!(requests[1].header("Authorization"), Some("Bearer a1"));
+    assert!(requests[2].body.contains("refresh_token=r2"));
+    assert_eq!(requests[3].header("Authorization"), Some("Bearer a2"));
+
+    let refresh_tokens: Vec<String> = refreshed
+        .lock()
+        .unwrap()
+        .iter()
+        .map(|tokens| tokens.refresh_token.clone())
+        .collect();
+    assert_eq!(refresh_tokens, vec!["r2", "r3"]);
+}
+
+#[tokio::test]
+async fn gives_up_after_a_second_401() {
+    let server = MockServer::start(vec![
+        token("a1", "r2"),
+        Response::json(401, r#"{"message":"unauthorized"}"#),
+        token("a2", "r3"),
+        Response::json(401, r#"{"message":"unauthorized"}"#),
+    ])
+    .await;
+    let (_, on_refresh) = recorder();
+
+    let gateway = builder(&server)
+        .oauth(client(&server), "r1".to_string(), on_refresh)
+        .build()
+        .await
+        .unwrap();
+    let err = gateway.get_customer("1").await.unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::Unauthorized);
+    assert_eq!(err.status(), Some(401));
+    assert_eq!(server.requests().len(), 4);
+}
+
+#[tokio::test]
+async fn reports_a_rejected_refresh_token_when_built() {
+    let server = MockServer::start(vec![Response::json(
+        400,
+        r#"{"error":"invalid_grant","error_description":"Invalid refresh token"}"#,
+    )])
+    .await;
+    let (refreshed, on_refresh) = recorder();
+
+    let err = match builder(&server)
+        .oauth(client(&server), "r1".to_string(), on_refresh)
+        .build()
+        .await
+    {
+        Ok(_) => panic!("expected the refresh to fail"),
+        Err(err) => err,
+    };
+    assert_eq!(err.kind(), ErrorKind::Unauthorized);
+    assert!(err.message().contains("invalid_grant"));
+    assert!(refreshed.lock().unwrap().is_empty());
+}
+
+#[tokio::test]
+async fn with_oauth_allows_a_plain_http_token_url() {
+    let server = MockServer::start(vec![token("a1", "r2")]).await;
+    let (refreshed, on_refresh) = recorder();
+
+    Gateway::with_oauth(client(&server), "r1".to_string(), on_refresh, None)
+        .await
+        .unwrap();
+    assert_eq!(refreshed.lock().unwrap().len(), 1);
+}
+
+#[tokio::test]
+async fn refuses_a_plain_http_token_url_by_default() {
+    let server = MockServer::start(vec![token("a1", "r2")]).await;
+    let mut client = client(&server);
+    client.https_only = true;
+
+    assert!(Gateway::with_oauth(client, "r1".to_string(), |_| {}, None)
+        .await
+        .is_err());
+    assert!(server.requests().is_empty());
+}
+
+#[tokio::test]
+async fn writes_rotated_tokens_to_the_store() {
+    let server = MockServer::start(vec![token("a1", "r2"), customer("1")]).await;
+    let store = Arc::new(InMemoryTokenStore::new(Some(TokenSet::from_refresh_token(
+        "r1".to_string(),
+    ))));
+
+    let gateway = builder(&server)
+        .token_store(client(&server), store.clone())
+        .build()
+        .await
+        .unwrap();
+    gateway.get_customer("1").await.unwrap();
+
+    let stored = store.load().unwrap().unwrap();
+    assert_eq!(stored.access_token, "a1");
+    assert_eq!(stored.refresh_token, "r2");
+}
+
+#[tokio::test]
+async fn refreshes_with_tokens_rotated_by_someone_else() {
+    let server = MockServer::start(vec![
+        token("a1", "r2"),
+        Response::json(401, r#"{"message":"unauthorized"}"#),
+        token("a4", "r4"),
+        customer("1"),
+    ])
+    .await;
+    let store = Arc::new(InMemoryTokenStore::new(Some(TokenSet::from_refresh_token(
+        "r1".to_string(),
+    ))));
+
+    let gateway = builder(&server)
+        .token_store(client(&server), store.clone())
+        .build()
+        .await
+        .unwrap();
+
+    // Another process rotates the tokens, and its access token then expires.
+    store
+        .save(&TokenSet {
+            access_token: "a3".to_string(),
+            refresh_token: "r3".to_string(),
+            scope: String::new(),
+            expires_at: Utc::now() - Duration::minutes(1),
+        })
+        .unwrap();
+
+    gateway.get_customer("1").await.unwrap();
+
+    let requests = server.requests();
+    assert_eq!(requests.len(), 4);
+    assert!(requests[2].body.contains("refresh_token=r3"));
+    assert_eq!(requests[3].header("Authorization"), Some("Bearer a4"));
+    assert_eq!(store.load().unwrap().unwrap().refresh_token, "r4");
+}
+
+#[tokio::test]
+async fn reports_a_failing_token_endpoint_as_a_server_error() {
+    let server = MockServer::start(vec![Response::json(503, "Service Unavailable")]).await;
+    let store = Arc::new(InMemoryTokenStore::new(Some(TokenSet::from_refresh_token(
+        "r1".to_string(),
+    ))));
+
+    let started = Instant::now();
+    let err = match builder(&server)
+        .token_store(client(&server), store)
+        .build()
+        .await
+    {
+        Ok(_) => panic!("expected the refresh to fail"),
+        Err(err) => err,
+    };
+    assert_eq!(err.kind(), ErrorKind::Server);
+    assert!(!err.is_auth());
+    assert!(err.is_retryable());
+    assert_eq!(err.status(), Some(503));
+    assert_eq!(err.body(), Some("Service Unavailable"));
+
+    // Not mistaken for a refresh token spent by someone else, which would
+    // wait for the store.
+    assert!(started.elapsed() < StdDuration::from_millis(500));
+    assert_eq!(server.requests().len(), 1);
+}
+
+#[tokio::test]
+async fn reports_the_request_a_failed_refresh_was_for() {
+    let server = MockServer::start(vec![
+        token("a1", "r2"),
+        Response::json(401, r#"{"message":"unauthorized"}"#),
+        Response::json(502, "Bad Gateway"),
+    ])
+    .await;
+
+    let gateway = builder(&server)
+        .oauth(client(&server), "r1".to_string(), |_| {})
+        .build()
+        .await
+        .unwrap();
+    let err = gateway.get_customer("1").await.unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::Server);
+    assert_eq!(err.status(), Some(502));
+    assert_eq!(err.method(), Some("GET"));
+    assert_eq!(err.path(), Some("/customers/1"));
+}
+
+#[tokio::test]
+async fn retries_a_failed_refresh() {
+    let server = MockServer::start(vec![
+        token("a1", "r2"),
+        Response::json(401, r#"{"message":"unauthorized"}"#),
+        Response::json(503, "Service Unavailable"),
+        token("a2", "r3"),
+        customer("1"),
+    ])
+    .await;
+    let (refreshed, on_refresh) = recorder();
+
+    let gateway = builder(&server)
+        .retry_policy(RetryPolicy::default())
+        .oauth(client(&server), "r1".to_string(), on_refresh)
+        .build()
+        .await
+        .unwrap();
+    gateway.get_customer("1").await.unwrap();
+
+    let requests = server.requests();
+    assert_eq!(requests.len(), 5);
+    assert!(requests[2].body.contains("refresh_token=r2"));
+    assert!(requests[3].body.contains("refresh_token=r2"));
+    assert_eq!(requests[4].header("Authorization"), Some("Bearer a2"));
+    assert_eq!(refreshed.lock().unwrap().len(), 2);
+}
+
+#[tokio::test]
+async fn retries_a_post_whose_access_token_could_not_be_refreshed() {
+    let server = MockServer::start(vec![
+        token("a1", "r2"),
+        Response::json(401, r#"{"message":"unauthorized"}"#),
+        Response::json(503, "Service Unavailable"),
+        token("a2", "r3"),
+        customer("1"),
+    ])
+    .await;
+
+    let gateway = builder(&server)
+        .retry_policy(RetryPolicy::default())
+        .oauth(client(&server), "r1".to_string(), |_| {})
+        .build()
+        .await
+        .unwrap();
+    gateway
+        .add_customer(&Customer::new("Acme".to_string()))
+        .await
+        .unwrap();
+
+    let requests = server.requests();
+    assert_eq!(requests.len(), 5);
+    assert_eq!(requests[1].method, "POST");
+    assert_eq!(requests[4].method, "POST");
+    assert_eq!(requests[4].header("Authorization"), Some("Bearer a2"));
+}
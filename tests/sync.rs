@@ -0,0 +1,117 @@
+mod common;
+
+use common::{MockServer, Response};
+use fortnox::{GatewayBuilder, RetryPolicy, SyncCursor};
+
+fn builder(server: &MockServer) -> GatewayBuilder {
+    GatewayBuilder::new()
+        .access_token("token".to_string(), "secret".to_string())
+        .base_url(server.url.clone())
+        .https_only(false)
+        .retry_policy(RetryPolicy::none())
+}
+
+fn page(customers: &[&str]) -> Response {
+    let items: Vec<String> = customers
+        .iter()
+        .map(|number| format!(r#"{{"CustomerNumber":"{}","Name":"Acme"}}"#, number))
+        .collect();
+    Response::json(
+        200,
+        &format!(
+            r#"{{"MetaInformation":{{"@TotalResources":{},"@TotalPages":1,"@CurrentPage":1}},"Customers":[{}]}}"#,
+            items.len(),
+            items.join(",")
+        ),
+    )
+}
+
+// The whole record, which has fields the list does not show.
+fn record(number: &str, comments: &str) -> Response {
+    Response::json(
+        200,
+        &format!(
+            r#"{{"Customer":{{"CustomerNumber":"{}","Name":"Acme","Comments":"{}"}}}}"#,
+            number, comments
+        ),
+    )
+}
+
+fn numbers(customers: &[fortnox::CustomerListItem]) -> Vec<&str> {
+    customers
+        .iter()
+        .map(|customer| customer.customer_number.as_str())
+        .collect()
+}
+
+#[tokio::test]
+async fn skips_boundary_records_that_have_not_changed() {
+    let server = MockServer::start(vec![
+        // First sync: everything, then the records the next sync will see
+        // again, and the whole of those.
+        page(&["1", "2"]),
+        page(&["2"]),
+        record("2", "First"),
+        // Second sync: record 2 is listed again but has not changed.
+        page(&["2", "3"]),
+        record("2", "First"),
+        page(&[]),
+    ])
+    .await;
+    let gateway = builder(&server).build().await.unwrap();
+    let mut cursor = SyncCursor::new();
+
+    let first = gateway.sync_customers(&mut cursor).await.unwrap();
+    assert_eq!(numbers(&first), vec!["1", "2"]);
+    assert!(cursor.synced_at("customers").is_some());
+
+    let second = gateway.sync_customers(&mut cursor).await.unwrap();
+    assert_eq!(numbers(&second), vec!["3"]);
+
+    let requests = server.requests();
+    assert_eq!(requests.len(), 6);
+    assert!(!requests[0].path.contains("lastmodified"));
+    assert!(requests[0].path.contains("sortby=customernumber"));
+    assert!(requests[1].path.contains("lastmodified"));
+    assert_eq!(requests[2].path, "/customers/2");
+    assert!(requests[3].path.contains("lastmodified"));
+    assert_eq!(requests[4].path, "/customers/2");
+}
+
+#[tokio::test]
+async fn returns_boundary_records_changed_outside_the_list_fields() {
+    let server = MockServer::start(vec![
+        page(&["1"]),
+        page(&["1"]),
+        record("1", "First"),
+        // The list item is the same, but the record is not.
+        page(&["1"]),
+        record("1", "Second"),
+        page(&[]),
+    ])
+    .await;
+    let gateway = builder(&server).build().await.unwrap();
+    let mut cursor = SyncCursor::new();
+
+    gateway.sync_customers(&mut cursor).await.unwrap();
+    let second = gateway.sync_customers(&mut cursor).await.unwrap();
+    assert_eq!(numbers(&second), vec!["1"]);
+}
+
+#[tokio::test]
+async fn leaves_the_cursor_alone_when_a_sync_fails() {
+    let server = MockServer::start(vec![
+        page(&["1"]),
+        page(&[]),
+        Response::json(500, "Internal Server Error"),
+    ])
+    .await;
+    let gateway = builder(&server).build().await.unwrap();
+    let mut cursor = SyncCursor::new();
+
+    gateway.sync_customers(&mut cursor).await.unwrap();
+    let synced_at = cursor.synced_at("customers");
+
+    assert!(gateway.sync_customers(&mut cursor).await.is_err());
+    assert_eq!(cursor.synced_at("customers"), synced_at);
+}